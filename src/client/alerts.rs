@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use poise::serenity_prelude as serenity;
+use ::serenity::all::{ChannelId, CreateAttachment, CreateMessage, UserId};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::server::{
+    classes::netflix::client::{SessionState, NETFLIX},
+    config::{AdminConfig, CONFIG},
+    diagnostics::{Bundle, DIAGNOSTICS},
+};
+
+/// Tells the configured admins when the Netflix cookies stop working, once per expiry.
+pub fn spawn_session_alerts(http: Arc<serenity::Http>) {
+    let admin = CONFIG.admin.clone();
+    if admin.channel_id.is_none() && admin.user_id.is_none() {
+        return;
+    }
+    let mut updates = NETFLIX.subscribe();
+    tokio::spawn(async move {
+        let mut expired = matches!(*updates.borrow(), SessionState::NeedsReauth { .. });
+        while updates.changed().await.is_ok() {
+            let state = updates.borrow_and_update().clone();
+            match state {
+                SessionState::NeedsReauth { reason, .. } if !expired => {
+                    expired = true;
+                    let message = format!(
+                        "⚠️ The Netflix session has expired ({}). Export fresh cookies and restart the bot; searches will fail until then.",
+                        reason
+                    );
+                    notify(&http, &admin, &message).await;
+                }
+                SessionState::NeedsReauth { .. } => {}
+                _ => expired = false,
+            }
+        }
+    });
+}
+
+/// Posts each browser failure's report and screenshot to the admin channel.
+pub fn spawn_diagnostics_alerts(http: Arc<serenity::Http>) {
+    let Some(channel_id) = CONFIG.admin.channel_id.filter(|_| CONFIG.diagnostics.attach_to_admin) else {
+        return;
+    };
+    let mut bundles = DIAGNOSTICS.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match bundles.recv().await {
+                Ok(bundle) => post_bundle(&http, ChannelId::new(channel_id), &bundle).await,
+                Err(RecvError::Lagged(skipped)) => eprintln!("Skipped posting {} diagnostics bundles", skipped),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+async fn post_bundle(http: &serenity::Http, channel_id: ChannelId, bundle: &Bundle) {
+    let mut message = CreateMessage::new().content(format!(
+        "🛑 The browser failed during **{}**: {}\nBundle saved to `{}`",
+        bundle.step,
+        bundle.error.chars().take(1500).collect::<String>(),
+        bundle.dir.display()
+    ));
+    for path in std::iter::once(bundle.report()).chain(bundle.screenshot()) {
+        match CreateAttachment::path(&path).await {
+            Ok(attachment) => message = message.add_file(attachment),
+            Err(err) => eprintln!("Couldn't attach {:?}: {:?}", path, err),
+        }
+    }
+    if let Err(err) = channel_id.send_message(http, message).await {
+        eprintln!("Failed to post diagnostics: {:?}", err);
+    }
+}
+
+async fn notify(http: &serenity::Http, admin: &AdminConfig, message: &str) {
+    if let Some(channel_id) = admin.channel_id {
+        if let Err(err) = ChannelId::new(channel_id).say(http, message).await {
+            eprintln!("Failed to post admin alert: {:?}", err);
+        }
+    }
+    if let Some(user_id) = admin.user_id {
+        if let Err(err) = UserId::new(user_id).direct_message(http, CreateMessage::new().content(message)).await {
+            eprintln!("Failed to DM admin alert: {:?}", err);
+        }
+    }
+}
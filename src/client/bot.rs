@@ -0,0 +1,141 @@
+#![warn(clippy::str_to_string)]
+
+use crate::client::{alerts, commands, flow::{Flow, LiveBackend}, render};
+use std::env;
+use poise::serenity_prelude as serenity;
+use ::serenity::all::GuildId;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+pub type Context<'a> = poise::Context<'a, Data, Error>;
+
+
+pub struct Data {
+    pub votes: Mutex<HashMap<String, u32>>,
+    pub flow: Flow,
+}
+
+async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
+    
+    
+    
+    match error {
+        poise::FrameworkError::Setup { error, .. } => panic!("Failed to start bot: {:?}", error),
+        poise::FrameworkError::Command { error, ctx, .. } => {
+            println!("Error in command `{}`: {:?}", ctx.command().name, error,);
+        }
+        error => {
+            if let Err(e) = poise::builtins::on_error(error).await {
+                println!("Error while handling error: {}", e)
+            }
+        }
+    }
+}
+
+async fn event_handler(
+    _ctx: &serenity::Context,
+    event: &serenity::FullEvent,
+    _framework: poise::FrameworkContext<'_, Data, Error>,
+    _data: &Data,
+) -> Result<(), Error> {
+    match event {
+        serenity::FullEvent::Ready { data_about_bot, .. } => {
+            println!("Logged in as {}", data_about_bot.user.name);
+        }
+
+        serenity::FullEvent::InteractionCreate { interaction } => {
+            if let Some(component_interaction) = interaction.clone().message_component() {
+                if let Some(interaction_event) = render::parse_event(&component_interaction) {
+                    if let Err(err) = component_interaction.defer(&_ctx.http).await {
+                        eprintln!("Failed to defer interaction: {:?}", err);
+                        return Ok(()); 
+                    }
+                    let sink = render::MessageSink::new(_ctx.http.clone(), &component_interaction);
+                    _data.flow.handle(interaction_event, &sink).await?;
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+pub async fn main() {
+
+    
+    
+    let options = poise::FrameworkOptions {
+        commands: vec![commands::help(), commands::vote(), commands::getvotes(), commands::watch(), commands::browse(), commands::info(), commands::stop(), commands::pause(), commands::seek(), commands::volume(), commands::quality(), commands::captions(), commands::skip_to(), commands::status(), commands::selectors(), commands::selector_override()],
+        prefix_options: poise::PrefixFrameworkOptions {
+            prefix: Some("~".into()),
+            edit_tracker: Some(Arc::new(poise::EditTracker::for_timespan(
+                Duration::from_secs(3600),
+            ))),
+            additional_prefixes: vec![
+                poise::Prefix::Literal("hey bot,"),
+                poise::Prefix::Literal("hey bot"),
+            ],
+            ..Default::default()
+        },
+        
+        on_error: |error| Box::pin(on_error(error)),
+        
+        pre_command: |ctx| {
+            Box::pin(async move {
+                println!("Executing command {}...", ctx.command().qualified_name);
+            })
+        },
+        
+        post_command: |ctx| {
+            Box::pin(async move {
+                println!("Executed command {}!", ctx.command().qualified_name);
+            })
+        },
+        
+        command_check: Some(|ctx| {
+            Box::pin(async move {
+                if ctx.author().id == 123456789 {
+                    return Ok(false);
+                }
+                Ok(true)
+            })
+        }),
+        
+        
+        skip_checks_for_owners: false,
+        event_handler: |_ctx: &::serenity::prelude::Context, event: &serenity::FullEvent, _framework, _data| {
+            Box::pin(event_handler(_ctx, event, _framework, _data))
+        },
+        ..Default::default()
+    };
+
+    let framework = poise::Framework::builder()
+        .setup(move |ctx, _ready, framework| {
+            Box::pin(async move {
+                println!("Logged in as {}", _ready.user.name);
+                poise::builtins::register_in_guild(ctx, &framework.options().commands, GuildId::new(1369273109303132170)).await?;
+                alerts::spawn_session_alerts(ctx.http.clone());
+                alerts::spawn_diagnostics_alerts(ctx.http.clone());
+                Ok(Data {
+                    votes: Mutex::new(HashMap::new()),
+                    flow: Flow::new(Arc::new(LiveBackend)),
+                })
+            })
+        })
+        .options(options)
+        .build();
+
+    let token = env::var("DISCORD_BOT_TOKEN")
+        .expect("expected token in env");
+    let intents =
+        serenity::GatewayIntents::non_privileged() | serenity::GatewayIntents::MESSAGE_CONTENT;
+
+    let client = serenity::ClientBuilder::new(token, intents)
+        .framework(framework)
+        .await;
+
+    client.unwrap().start().await.unwrap()
+}
@@ -1,11 +1,10 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, sync::Arc};
 
-use crate::{client::client::{Context, Error}, server::{classes::{fasel::Fasel, netflix::{Netflix, NetflixSearcher, ShowResult}}, functions::quit_browser}};
+use crate::{client::{bot::{Context, Error}, flow::report_failures, render, views::View}, server::{browser::{jwplayer::{JwPlayer, JwState}, selectors::{Outcome, SELECTORS}, BrowserBackend}, classes::{netflix::{client::{SessionState, NETFLIX}, Netflix, ShowResult}, provider, provider_names, shahid::Shahid, BrowseList, TitleDetails}, config::CONFIG, functions::{current_driver, current_service, quit_browser, uses_jwplayer}}};
+use futures::{stream, StreamExt};
 use image::{DynamicImage, ImageBuffer, Rgba};
-use poise::serenity_prelude::CreateAttachment;
-use serenity::all::{CreateEmbed, EditAttachments, EditMessage};
-
-use crate::server::classes::fasel::FaselSearcher;
+use poise::{serenity_prelude::CreateAttachment, CreateReply};
+use serenity::all::{EditAttachments, Message};
 #[poise::command(prefix_command, track_edits, slash_command)]
 pub async fn help(
     ctx: Context<'_>,
@@ -46,7 +45,7 @@ async fn fasel_autocomplete<'a>(
     _ctx: Context<'a>,
     _partial: &str,
 ) -> Vec<String> {
-    vec!["Netflix".to_string(), "Fasel".to_string()]
+    provider_names().into_iter().map(str::to_string).collect()
 }
 #[poise::command(slash_command)]
 pub async fn watch(
@@ -60,54 +59,110 @@ pub async fn watch(
         Cow::Borrowed(msg) => msg.clone(),
     };
 
-    let movie_data = fetch_movies(&movie, &streamingservice).await?;
+    let movie_data = match fetch_movies(&movie, &streamingservice).await {
+        Ok(movie_data) => movie_data,
+        Err(err) => {
+            ctx.say(format!("Search failed: {}", err)).await?;
+            return Ok(());
+        }
+    };
     if movie_data.is_empty() {
         ctx.say("Couldn't find movie.").await?;
         return Ok(());
     }
 
-    let buffer = create_movie_collage(&movie_data).await?;
-    let new_attachment = CreateAttachment::bytes(buffer, "movies.png".to_string());
-    let attachment = EditAttachments::new().add(new_attachment);
+    show_results(ctx, &mut message, &movie, &streamingservice, &movie_data).await
+}
 
-    let embed = CreateEmbed::default()
-        .title(format!("Here are movies for '{}'", movie))
-        .description(format!("Select a movie to watch on {}", streamingservice))
-        .image("attachment://movies.png")
-        .to_owned();
+async fn browse_autocomplete<'a>(
+    _ctx: Context<'a>,
+    _partial: &str,
+) -> Vec<String> {
+    BrowseList::NAMES.iter().map(|name| name.to_string()).collect()
+}
 
-    let options: Vec<poise::serenity_prelude::CreateSelectMenuOption> = movie_data
-        .iter()
-        .map(|(_, _, title, _)| poise::serenity_prelude::CreateSelectMenuOption::new(title.clone(), title.clone()))
-        .collect();
+/// Shows a curated list (Top 10, Trending, My List, Continue Watching or a genre) to pick from.
+#[poise::command(slash_command)]
+pub async fn browse(
+    ctx: Context<'_>,
+    #[autocomplete = "browse_autocomplete"] list: String,
+    #[description = "Genre id, for the Genre list"] genre: Option<u32>,
+    #[autocomplete = "fasel_autocomplete"] streamingservice: Option<String>,
+) -> Result<(), Error> {
+    let streamingservice = streamingservice.unwrap_or_else(|| "Netflix".to_string());
+    let Some(browse_list) = BrowseList::parse(&list, genre) else {
+        ctx.say(format!("Pick one of {} (Genre needs a genre id)", BrowseList::NAMES.join(", "))).await?;
+        return Ok(());
+    };
+    let Some(provider) = provider(&streamingservice) else {
+        ctx.say("Streaming service not supported").await?;
+        return Ok(());
+    };
 
-    let select_menu = poise::serenity_prelude::CreateSelectMenu::new(
-        format!("movieSelect:{}", streamingservice),
-        serenity::all::CreateSelectMenuKind::String { options },
-    )
-    .placeholder("Select a movie");
+    let loading_message = ctx.say("Loading...").await?;
+    let mut message = match loading_message.message().await? {
+        Cow::Owned(msg) => msg,
+        Cow::Borrowed(msg) => msg.clone(),
+    };
+    let movie_data = match provider.browse(&browse_list).await {
+        Ok(movie_data) => movie_data,
+        Err(err) => {
+            ctx.say(format!("Browse failed: {}", err)).await?;
+            return Ok(());
+        }
+    };
+    if movie_data.is_empty() {
+        ctx.say(format!("{} is empty.", browse_list.name())).await?;
+        return Ok(());
+    }
 
-    let action_row = poise::serenity_prelude::CreateActionRow::SelectMenu(select_menu).to_owned();
+    show_results(ctx, &mut message, &browse_list.name(), provider.name(), &movie_data).await
+}
+
+/// Replaces the loading message with the collage and the select menu for `movie_data`.
+async fn show_results(
+    ctx: Context<'_>,
+    message: &mut Message,
+    heading: &str,
+    streamingservice: &str,
+    movie_data: &[(String, String, String, ShowResult)],
+) -> Result<(), Error> {
+    let buffer = create_movie_collage(movie_data).await?;
+    let new_attachment = CreateAttachment::bytes(buffer, "movies.png".to_string());
+    let attachment = EditAttachments::new().add(new_attachment);
 
-    let reply = EditMessage::new()
-        .content("")
-        .embed(embed.clone())
-        .components(vec![action_row])
+    let details = fetch_details(streamingservice, movie_data).await;
+    let reply = render::edit_message(&View::search_results(heading, streamingservice, movie_data, &details))
         .attachments(attachment);
 
     message.edit(ctx.http(), reply).await?;
     Ok(())
 }
 
-async fn fetch_movies(movie: &str, streamingservice: &str) -> Result<Vec<(String, String, String, ShowResult)>, Error> {
-    match streamingservice.to_lowercase().as_str() {
-        "fasel" => FaselSearcher::_search(movie).await,
-        "netflix" => NetflixSearcher::search(movie).await,
-        _ => Err("Streaming service not supported".into()),
+pub async fn fetch_movies(movie: &str, streamingservice: &str) -> Result<Vec<(String, String, String, ShowResult)>, Error> {
+    match provider(streamingservice) {
+        Some(provider) => provider.search(movie).await,
+        None => Err("Streaming service not supported".into()),
     }
 }
 
-async fn create_movie_collage(movie_data: &Vec<(String, String, String, ShowResult)>) -> Result<Vec<u8>, Error> {
+/// How many detail pages are fetched at once, so a page of results doesn't burst the provider.
+const DETAIL_LOOKUPS: usize = 4;
+
+/// Details for each result, where the provider has them; used for the select menu descriptions.
+async fn fetch_details(streamingservice: &str, movie_data: &[(String, String, String, ShowResult)]) -> Vec<Option<TitleDetails>> {
+    let Some(provider) = provider(streamingservice) else {
+        return vec![None; movie_data.len()];
+    };
+    let urls: Vec<String> = movie_data.iter().map(|(url, _, _, _)| url.clone()).collect();
+    stream::iter(urls)
+        .map(|url| async move { provider.details(&url).await.ok() })
+        .buffered(DETAIL_LOOKUPS)
+        .collect()
+        .await
+}
+
+async fn create_movie_collage(movie_data: &[(String, String, String, ShowResult)]) -> Result<Vec<u8>, Error> {
     let movie_count = movie_data.len() as u32;
     let max_columns = (movie_count as f64).sqrt().ceil() as u32;
     let max_rows = (movie_count as f64 / max_columns as f64).ceil() as u32;
@@ -151,6 +206,30 @@ async fn create_movie_collage(movie_data: &Vec<(String, String, String, ShowResu
 }
 
 
+/// Shows the synopsis, year, rating and cast of the best match for a title.
+#[poise::command(slash_command)]
+pub async fn info(
+    ctx: Context<'_>,
+    #[description = "Title to look up"] title: String,
+    #[autocomplete = "fasel_autocomplete"] streamingservice: String,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let Some(provider) = provider(&streamingservice) else {
+        ctx.say("Streaming service not supported").await?;
+        return Ok(());
+    };
+    let Some((url, image)) = provider.best_match(&title).await? else {
+        ctx.say("Couldn't find movie.").await?;
+        return Ok(());
+    };
+    let view = match provider.details(&url).await {
+        Ok(details) => View::title_details(&details, &image),
+        Err(err) => View::error(&err.to_string()),
+    };
+    ctx.send(CreateReply::default().embed(render::embed(&view))).await?;
+    Ok(())
+}
+
 #[poise::command(slash_command)]
 pub async fn stop(
     ctx: Context<'_>,
@@ -163,19 +242,217 @@ pub async fn stop(
 pub async fn skip_to(
     ctx: Context<'_>
 ) -> Result<(), Error> {
-    Netflix::skip_to_specific_timeline("0.20").await?;
+    if let Some(driver) = current_driver().await {
+        report_failures(driver, "skip_to", |driver| async move {
+            Netflix::new(driver).skip_to_specific_timeline("0.20").await
+        })
+        .await?;
+    }
     ctx.reply("Skipped").await?;
     Ok(())
 }
 
+/// Reports whether the providers can be used right now.
+#[poise::command(slash_command)]
+pub async fn status(
+    ctx: Context<'_>,
+) -> Result<(), Error> {
+    let netflix = match NETFLIX.session() {
+        SessionState::Unknown => "not checked yet".to_string(),
+        SessionState::Active => "logged in".to_string(),
+        SessionState::NeedsReauth { reason, since } => {
+            let minutes = since.elapsed().map(|elapsed| elapsed.as_secs() / 60).unwrap_or(0);
+            format!("needs re-auth ({}, for {} min)", reason, minutes)
+        }
+    };
+    let browser = if current_driver().await.is_some() { "running" } else { "idle" };
+    ctx.reply(format!("Netflix: {}\nBrowser: {}", netflix, browser)).await?;
+    Ok(())
+}
+
+fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// The browser streaming the page, when the current service plays through a JW Player.
+async fn current_jwplayer() -> Option<Arc<dyn BrowserBackend>> {
+    let driver = current_driver().await?;
+    current_service().await.filter(|service| uses_jwplayer(service))?;
+    Some(driver)
+}
+
+fn describe(state: &JwState) -> String {
+    let mut parts = vec![
+        format!("{} {} / {}", state.state, format_timestamp(state.position), format_timestamp(state.duration)),
+        format!("Volume {}%", state.volume),
+    ];
+    if let Some(quality) = state.quality_label() {
+        parts.push(format!("Quality {}", quality));
+    }
+    if let Some(captions) = state.caption_label() {
+        parts.push(format!("Captions {}", captions));
+    }
+    parts.join(" · ")
+}
+
 #[poise::command(slash_command)]
 pub async fn pause(
-    _ctx: Context<'_>,
+    ctx: Context<'_>,
 ) -> Result<(), Error> {
-    Fasel::pause().await?;
+    if let Some(driver) = current_jwplayer().await {
+        let state = report_failures(driver, "pause", |driver| async move { JwPlayer::new(driver).pause().await }).await?;
+        ctx.reply(describe(&state)).await?;
+    } else if let Some(driver) = current_driver().await {
+        if current_service().await.as_deref() == Some("Shahid") {
+            let state = report_failures(driver, "pause", |driver| async move { Shahid::new(driver).toggle().await }).await?;
+            ctx.reply(if state.paused { "Paused" } else { "Playing" }).await?;
+            return Ok(());
+        }
+        report_failures(driver, "pause", |driver| async move { Netflix::new(driver).pause().await }).await?;
+        ctx.reply("Toggled playback").await?;
+    } else {
+        ctx.reply("Nothing is playing").await?;
+    }
+    Ok(())
+}
+
+/// Jumps a JW Player stream to a position.
+#[poise::command(slash_command)]
+pub async fn seek(
+    ctx: Context<'_>,
+    #[description = "Position in minutes"] minutes: f64,
+) -> Result<(), Error> {
+    let Some(driver) = current_jwplayer().await else {
+        ctx.reply("Seeking only works for JW Player streams").await?;
+        return Ok(());
+    };
+    let state = report_failures(driver, "seek", |driver| async move { JwPlayer::new(driver).seek(minutes * 60.0).await }).await?;
+    ctx.reply(describe(&state)).await?;
     Ok(())
 }
 
+/// Sets the volume of a JW Player stream, from 0 to 100.
+#[poise::command(slash_command)]
+pub async fn volume(
+    ctx: Context<'_>,
+    #[description = "Volume in percent"] percent: u8,
+) -> Result<(), Error> {
+    let Some(driver) = current_jwplayer().await else {
+        ctx.reply("Volume only works for JW Player streams").await?;
+        return Ok(());
+    };
+    let state = report_failures(driver, "volume", |driver| async move { JwPlayer::new(driver).set_volume(percent).await }).await?;
+    ctx.reply(describe(&state)).await?;
+    Ok(())
+}
+
+/// Switches a JW Player stream to another quality level, such as 720p or Auto.
+#[poise::command(slash_command)]
+pub async fn quality(
+    ctx: Context<'_>,
+    #[description = "Quality label, as listed by the player"] label: String,
+) -> Result<(), Error> {
+    let Some(driver) = current_jwplayer().await else {
+        ctx.reply("Quality only works for JW Player streams").await?;
+        return Ok(());
+    };
+    match report_failures(driver, "quality", |driver| async move { JwPlayer::new(driver).set_quality(&label).await }).await {
+        Ok(state) => ctx.reply(describe(&state)).await?,
+        Err(err) => ctx.reply(err.to_string()).await?,
+    };
+    Ok(())
+}
+
+/// Picks the captions of a JW Player stream by language, or Off.
+#[poise::command(slash_command)]
+pub async fn captions(
+    ctx: Context<'_>,
+    #[description = "Captions track, as listed by the player"] track: String,
+) -> Result<(), Error> {
+    let Some(driver) = current_jwplayer().await else {
+        ctx.reply("Captions only work for JW Player streams").await?;
+        return Ok(());
+    };
+    match report_failures(driver, "captions", |driver| async move { JwPlayer::new(driver).set_captions(&track).await }).await {
+        Ok(state) => ctx.reply(describe(&state)).await?,
+        Err(err) => ctx.reply(err.to_string()).await?,
+    };
+    Ok(())
+}
+
+
+/// Lets only the configured admin through.
+async fn is_admin(ctx: Context<'_>) -> Result<bool, Error> {
+    Ok(CONFIG.admin.user_id == Some(ctx.author().id.get()))
+}
+
+async fn selector_autocomplete<'a>(
+    _ctx: Context<'a>,
+    partial: &str,
+) -> Vec<String> {
+    SELECTORS.names().into_iter().filter(|name| name.contains(partial)).collect()
+}
+
+/// Reports which fallback each DOM selector last matched, optionally probing the open page.
+#[poise::command(slash_command, check = "is_admin")]
+pub async fn selectors(
+    ctx: Context<'_>,
+    #[description = "Look every selector up on the page in the browser now"] probe: Option<bool>,
+) -> Result<(), Error> {
+    if probe.unwrap_or(false) {
+        let Some(driver) = current_driver().await else {
+            ctx.reply("The browser isn't running").await?;
+            return Ok(());
+        };
+        for name in SELECTORS.names().into_iter().filter(|name| !SELECTORS.needs_vars(name)) {
+            let _ = SELECTORS.find(driver.as_ref(), &name, &[]).await;
+        }
+    }
+    let mut lines = vec![format!("Selector registry v{}", SELECTORS.version())];
+    for name in SELECTORS.names() {
+        let source = if SELECTORS.is_overridden(&name) { " (override)" } else { "" };
+        let outcome = match SELECTORS.outcome(&name) {
+            None if probe.unwrap_or(false) && SELECTORS.needs_vars(&name) => "needs vars, not probed".to_string(),
+            Some(Outcome::Matched { index, selector }) => {
+                format!("matched #{} `{}`", index + 1, selector.chars().take(60).collect::<String>())
+            }
+            Some(Outcome::Failed { error: None }) => "failed".to_string(),
+            Some(Outcome::Failed { error: Some(error) }) => format!("failed: {}", error.chars().take(80).collect::<String>()),
+            None => "not used yet".to_string(),
+        };
+        lines.push(format!("`{}`{}: {}", name, source, outcome));
+    }
+    ctx.reply(lines.join("\n")).await?;
+    Ok(())
+}
+
+/// Replaces a DOM selector's fallbacks until restart; leave `selectors` out to reset it.
+#[poise::command(slash_command, check = "is_admin")]
+pub async fn selector_override(
+    ctx: Context<'_>,
+    #[autocomplete = "selector_autocomplete"] element: String,
+    #[description = "Fallbacks separated by ||, prefixed with xpath: for XPath"] selectors: Option<String>,
+) -> Result<(), Error> {
+    let fallbacks: Vec<String> = selectors
+        .unwrap_or_default()
+        .split("||")
+        .map(str::trim)
+        .filter(|selector| !selector.is_empty())
+        .map(str::to_string)
+        .collect();
+    let reply = match SELECTORS.set_override(&element, fallbacks) {
+        Ok(()) => format!("`{}` now tries: {}", element, SELECTORS.selectors(&element).join(" || ")),
+        Err(err) => err,
+    };
+    ctx.reply(reply).await?;
+    Ok(())
+}
 
 #[poise::command(prefix_command, track_edits, aliases("votes"), slash_command)]
 pub async fn getvotes(
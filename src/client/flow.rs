@@ -0,0 +1,604 @@
+use std::{fmt::Display, future::Future, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::{
+    client::{bot::Error, commands::fetch_movies, views::View},
+    server::{
+        browser::{jwplayer::JwPlayer, BrowserBackend},
+        classes::{
+            netflix::{Netflix, ShowResult},
+            provider,
+            shahid::{client::{Playlist, Product, Season}, Shahid, ShahidSearcher},
+        },
+        diagnostics::{record_failure, Trace},
+        functions::{current_driver, current_service, quit_browser, uses_jwplayer},
+    },
+    Streamer,
+};
+
+/// A user action on one of the bot's messages, already decoded from the transport.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InteractionEvent {
+    MovieSelected { service: String, title: String },
+    SeasonSelected { service: String, season_id: String },
+    EpisodeSelected { service: String, season_id: String, episode_id: String },
+    SeasonPage { service: String, show_id: String, page: usize },
+    EpisodePage { service: String, season_id: String, page: usize },
+    ShahidSeasonSelected { season_id: String },
+    ShahidPlaylistSelected { playlist_id: String },
+    ShahidEpisodeSelected { playlist_id: String, episode_id: String },
+    ShahidSeasonPage { show_id: String, page: usize },
+    ShahidPlaylistPage { season_id: String, page: usize },
+    ShahidEpisodePage { playlist_id: String, page: usize },
+    Control(ControlAction),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlAction {
+    Pause,
+    Stop,
+    SkipFront,
+    SkipBack,
+}
+
+impl InteractionEvent {
+    /// Decodes a component `custom_id` (plus the selected values, for select menus).
+    /// Season and episode ids without a service predate Fasel series and are Netflix's.
+    pub fn parse(custom_id: &str, values: &[String]) -> Option<Self> {
+        let first = values.first().cloned();
+        let (kind, rest) = custom_id.split_once(':').unwrap_or((custom_id, ""));
+        let service = || if rest.is_empty() { "Netflix".to_owned() } else { rest.to_owned() };
+        match kind {
+            "movieSelect" if !rest.is_empty() => Some(InteractionEvent::MovieSelected { service: rest.to_owned(), title: first? }),
+            "seasonSelect" => Some(InteractionEvent::SeasonSelected { service: service(), season_id: first? }),
+            "episodeSelect" => {
+                let value = first?;
+                let (season_id, episode_id) = value.split_once(':').unwrap_or(("", &value));
+                Some(InteractionEvent::EpisodeSelected {
+                    service: service(),
+                    season_id: season_id.to_owned(),
+                    episode_id: episode_id.to_owned(),
+                })
+            }
+            "seasonPage" | "episodePage" | "shahidSeasonPage" | "shahidPlaylistPage" | "shahidEpisodePage" => {
+                let parts: Vec<&str> = rest.split(':').collect();
+                let (service, id, page) = match parts[..] {
+                    [service, id, page] => (service.to_owned(), id.to_owned(), page.parse().ok()?),
+                    [id, page] => ("Netflix".to_owned(), id.to_owned(), page.parse().ok()?),
+                    _ => return None,
+                };
+                Some(match kind {
+                    "seasonPage" => InteractionEvent::SeasonPage { service, show_id: id, page },
+                    "episodePage" => InteractionEvent::EpisodePage { service, season_id: id, page },
+                    "shahidSeasonPage" => InteractionEvent::ShahidSeasonPage { show_id: id, page },
+                    "shahidPlaylistPage" => InteractionEvent::ShahidPlaylistPage { season_id: id, page },
+                    _ => InteractionEvent::ShahidEpisodePage { playlist_id: id, page },
+                })
+            }
+            "shahidSeasonSelect" => Some(InteractionEvent::ShahidSeasonSelected { season_id: first? }),
+            "shahidPlaylistSelect" => Some(InteractionEvent::ShahidPlaylistSelected { playlist_id: first? }),
+            "shahidEpisodeSelect" => {
+                let (playlist_id, episode_id) = first?.split_once(':').map(|(a, b)| (a.to_owned(), b.to_owned()))?;
+                Some(InteractionEvent::ShahidEpisodeSelected { playlist_id, episode_id })
+            }
+            "pause" => Some(InteractionEvent::Control(ControlAction::Pause)),
+            "stop" => Some(InteractionEvent::Control(ControlAction::Stop)),
+            "skipfront" => Some(InteractionEvent::Control(ControlAction::SkipFront)),
+            "skipback" => Some(InteractionEvent::Control(ControlAction::SkipBack)),
+            _ => None,
+        }
+    }
+}
+
+/// The providers and the player, as seen by the interaction flow.
+#[async_trait]
+pub trait FlowBackend: Send + Sync {
+    async fn search(&self, service: &str, query: &str) -> Result<Vec<(String, String, String, ShowResult)>, Error>;
+    async fn show(&self, service: &str, show_id: &str) -> Result<ShowResult, Error>;
+    async fn episodes(&self, service: &str, season_id: &str) -> Result<Vec<(i64, String, i64)>, Error>;
+    async fn episode_url(&self, service: &str, season_id: &str, episode_id: &str) -> Result<String, Error>;
+    async fn shahid_seasons(&self, show_id: &str) -> Result<Vec<Season>, Error>;
+    async fn shahid_playlists(&self, season_id: &str) -> Result<Vec<Playlist>, Error>;
+    async fn shahid_episodes(&self, playlist_id: &str) -> Result<Vec<Product>, Error>;
+    async fn start_stream(&self, url: &str, service: &str) -> Result<(), Error>;
+    async fn control(&self, action: ControlAction) -> Result<(), Error>;
+}
+
+/// Receives every view the flow wants shown, in order.
+#[async_trait]
+pub trait ViewSink: Send + Sync {
+    async fn show(&self, view: View) -> Result<(), Error>;
+}
+
+/// The search → season → episode → controller state machine. Shahid shows add a
+/// playlist step between the season and the episode.
+pub struct Flow {
+    backend: Arc<dyn FlowBackend>,
+}
+
+impl Flow {
+    pub fn new(backend: Arc<dyn FlowBackend>) -> Self {
+        Flow { backend }
+    }
+
+    pub async fn handle(&self, event: InteractionEvent, sink: &dyn ViewSink) -> Result<(), Error> {
+        match event {
+            InteractionEvent::MovieSelected { service, title } => {
+                println!("User selected: {}", title);
+                sink.show(View::loading()).await?;
+
+                let results = match self.backend.search(&service, &title).await {
+                    Ok(results) => results,
+                    Err(err) => return sink.show(View::error(&format!("Couldn't load '{}': {}", title, err))).await,
+                };
+                let Some((url, _, _, show_result)) = results.first() else {
+                    return sink.show(View::error(&format!("Couldn't find '{}' anymore.", title))).await;
+                };
+
+                if show_result.is_show && service.eq_ignore_ascii_case("shahid") {
+                    let show_id = show_result.show_data["id"].as_str().unwrap_or_default().to_owned();
+                    self.show_shahid_seasons(&show_id, 0, sink).await
+                } else if show_result.is_show {
+                    sink.show(View::season_select(&service, show_result, 0)).await
+                } else {
+                    self.play(url, &service, sink).await
+                }
+            }
+            InteractionEvent::SeasonSelected { service, season_id } => {
+                println!("User selected season ID: {}", season_id);
+                self.show_episodes(&service, &season_id, 0, sink).await
+            }
+            InteractionEvent::EpisodePage { service, season_id, page } => self.show_episodes(&service, &season_id, page, sink).await,
+            InteractionEvent::SeasonPage { service, show_id, page } => match self.backend.show(&service, &show_id).await {
+                Ok(show) => sink.show(View::season_select(&service, &show, page)).await,
+                Err(err) => {
+                    eprintln!("Failed to fetch seasons for show {}: {:?}", show_id, err);
+                    sink.show(View::error("Couldn't load the seasons for that show.")).await
+                }
+            },
+            InteractionEvent::EpisodeSelected { service, season_id, episode_id } => {
+                match self.backend.episode_url(&service, &season_id, &episode_id).await {
+                    Ok(url) => self.play(&url, &service, sink).await,
+                    Err(err) => {
+                        eprintln!("Failed to find episode {} of season {}: {:?}", episode_id, season_id, err);
+                        sink.show(View::error("Couldn't find that episode anymore.")).await
+                    }
+                }
+            }
+            InteractionEvent::ShahidSeasonPage { show_id, page } => self.show_shahid_seasons(&show_id, page, sink).await,
+            InteractionEvent::ShahidSeasonSelected { season_id } => self.show_shahid_playlists(&season_id, 0, sink).await,
+            InteractionEvent::ShahidPlaylistPage { season_id, page } => self.show_shahid_playlists(&season_id, page, sink).await,
+            InteractionEvent::ShahidPlaylistSelected { playlist_id } => self.show_shahid_episodes(&playlist_id, 0, sink).await,
+            InteractionEvent::ShahidEpisodePage { playlist_id, page } => self.show_shahid_episodes(&playlist_id, page, sink).await,
+            InteractionEvent::ShahidEpisodeSelected { playlist_id, episode_id } => {
+                let url = match self.backend.shahid_episodes(&playlist_id).await {
+                    Ok(episodes) => episodes
+                        .into_iter()
+                        .find(|episode| episode.id == episode_id)
+                        .and_then(|episode| episode.url().map(str::to_owned)),
+                    Err(err) => {
+                        eprintln!("Failed to fetch playlist {}: {:?}", playlist_id, err);
+                        None
+                    }
+                };
+                match url {
+                    Some(url) => self.play(&url, "Shahid", sink).await,
+                    None => sink.show(View::error("Couldn't find that episode anymore.")).await,
+                }
+            }
+            InteractionEvent::Control(action) => self.backend.control(action).await,
+        }
+    }
+
+    async fn show_shahid_seasons(&self, show_id: &str, page: usize, sink: &dyn ViewSink) -> Result<(), Error> {
+        match self.backend.shahid_seasons(show_id).await {
+            Ok(seasons) => sink.show(View::shahid_season_select(show_id, &seasons, page)).await,
+            Err(err) => {
+                eprintln!("Failed to fetch seasons for show {}: {:?}", show_id, err);
+                sink.show(View::error("Couldn't load the seasons for that show.")).await
+            }
+        }
+    }
+
+    async fn show_shahid_playlists(&self, season_id: &str, page: usize, sink: &dyn ViewSink) -> Result<(), Error> {
+        match self.backend.shahid_playlists(season_id).await {
+            Ok(playlists) if playlists.is_empty() => sink.show(View::error("That season has nothing to watch yet.")).await,
+            Ok(playlists) => sink.show(View::playlist_select(season_id, &playlists, page)).await,
+            Err(err) => {
+                eprintln!("Failed to fetch playlists for season {}: {:?}", season_id, err);
+                sink.show(View::error("Couldn't load the playlists for that season.")).await
+            }
+        }
+    }
+
+    async fn show_shahid_episodes(&self, playlist_id: &str, page: usize, sink: &dyn ViewSink) -> Result<(), Error> {
+        match self.backend.shahid_episodes(playlist_id).await {
+            Ok(episodes) => sink.show(View::shahid_episode_select(playlist_id, &episodes, page)).await,
+            Err(err) => {
+                eprintln!("Failed to fetch playlist {}: {:?}", playlist_id, err);
+                sink.show(View::error("Couldn't load the episodes for that playlist.")).await
+            }
+        }
+    }
+
+    async fn show_episodes(&self, service: &str, season_id: &str, page: usize, sink: &dyn ViewSink) -> Result<(), Error> {
+        match self.backend.episodes(service, season_id).await {
+            Ok(episodes) => sink.show(View::episode_select(service, season_id, &episodes, page)).await,
+            Err(err) => {
+                eprintln!("Failed to fetch episodes for season {}: {:?}", season_id, err);
+                sink.show(View::error("Couldn't load the episodes for that season.")).await
+            }
+        }
+    }
+
+    async fn play(&self, url: &str, service: &str, sink: &dyn ViewSink) -> Result<(), Error> {
+        sink.show(View::waiting_for_controller()).await?;
+        if let Err(err) = self.backend.start_stream(url, service).await {
+            eprintln!("Streamer Failed: {:?}", err);
+            return sink.show(View::error("The stream failed to start.")).await;
+        }
+        sink.show(View::controller()).await
+    }
+}
+
+/// The real providers and the shared browser session.
+pub struct LiveBackend;
+
+#[async_trait]
+impl FlowBackend for LiveBackend {
+    async fn search(&self, service: &str, query: &str) -> Result<Vec<(String, String, String, ShowResult)>, Error> {
+        fetch_movies(query, service).await
+    }
+
+    async fn show(&self, service: &str, show_id: &str) -> Result<ShowResult, Error> {
+        provider(service).ok_or("Streaming service not supported")?.show(show_id).await
+    }
+
+    async fn episodes(&self, service: &str, season_id: &str) -> Result<Vec<(i64, String, i64)>, Error> {
+        provider(service).ok_or("Streaming service not supported")?.episodes(season_id).await
+    }
+
+    async fn episode_url(&self, service: &str, season_id: &str, episode_id: &str) -> Result<String, Error> {
+        provider(service).ok_or("Streaming service not supported")?.episode_url(season_id, episode_id).await
+    }
+
+    async fn shahid_seasons(&self, show_id: &str) -> Result<Vec<Season>, Error> {
+        ShahidSearcher::seasons(show_id).await
+    }
+
+    async fn shahid_playlists(&self, season_id: &str) -> Result<Vec<Playlist>, Error> {
+        ShahidSearcher::playlists(season_id).await
+    }
+
+    async fn shahid_episodes(&self, playlist_id: &str) -> Result<Vec<Product>, Error> {
+        ShahidSearcher::episodes(playlist_id).await
+    }
+
+    async fn start_stream(&self, url: &str, service: &str) -> Result<(), Error> {
+        Streamer::start(url, service, "1000710976343134293").await?;
+        Ok(())
+    }
+
+    async fn control(&self, action: ControlAction) -> Result<(), Error> {
+        if action == ControlAction::Stop {
+            quit_browser().await?;
+            return Ok(());
+        }
+        let Some(driver) = current_driver().await else {
+            return Ok(());
+        };
+        report_failures(driver, &format!("{:?} control", action), |driver| control_player(driver, action)).await
+    }
+}
+
+/// Runs a player action as the trace step `step`, recording a diagnostics bundle
+/// from `driver` when it fails.
+pub(crate) async fn report_failures<T, E, F, Fut>(driver: Arc<dyn BrowserBackend>, step: &str, action: F) -> Result<T, E>
+where
+    E: Display,
+    F: FnOnce(Arc<dyn BrowserBackend>) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let trace = Trace::new();
+    trace.step(step);
+    let result = action(driver.clone()).await;
+    if let Err(err) = &result {
+        record_failure(Some(driver.as_ref()), &trace, &err.to_string()).await;
+    }
+    result
+}
+
+async fn control_player(driver: Arc<dyn BrowserBackend>, action: ControlAction) -> Result<(), Error> {
+    let service = current_service().await;
+    if service.as_deref() == Some("Shahid") {
+        let shahid = Shahid::new(driver);
+        match action {
+            ControlAction::Pause => shahid.toggle().await?,
+            ControlAction::SkipFront => shahid.seek_by(10.0).await?,
+            ControlAction::SkipBack => shahid.seek_by(-10.0).await?,
+            ControlAction::Stop => return Ok(()),
+        };
+    } else if service.is_some_and(|service| uses_jwplayer(&service)) {
+        let player = JwPlayer::new(driver);
+        match action {
+            ControlAction::Pause => player.toggle().await?,
+            ControlAction::SkipFront => player.seek_by(10.0).await?,
+            ControlAction::SkipBack => player.seek_by(-10.0).await?,
+            ControlAction::Stop => return Ok(()),
+        };
+    } else {
+        let netflix = Netflix::new(driver);
+        match action {
+            ControlAction::Pause => netflix.pause().await?,
+            ControlAction::SkipFront => netflix.skipfront().await?,
+            ControlAction::SkipBack => netflix.skipback().await?,
+            ControlAction::Stop => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::views::Component;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct FakeBackend {
+        results: Vec<(String, String, String, ShowResult)>,
+        calls: Mutex<Vec<String>>,
+        fail_stream: bool,
+        episode_count: i64,
+    }
+
+    #[async_trait]
+    impl FlowBackend for FakeBackend {
+        async fn search(&self, service: &str, query: &str) -> Result<Vec<(String, String, String, ShowResult)>, Error> {
+            self.calls.lock().unwrap().push(format!("search {} {}", service, query));
+            Ok(self.results.iter().map(|(url, img, title, show)| {
+                (url.clone(), img.clone(), title.clone(), ShowResult { show_data: show.show_data.clone(), is_show: show.is_show })
+            }).collect())
+        }
+
+        async fn show(&self, service: &str, show_id: &str) -> Result<ShowResult, Error> {
+            self.calls.lock().unwrap().push(format!("show {} {}", service, show_id));
+            Ok(movie(true).3)
+        }
+
+        async fn episode_url(&self, service: &str, _season_id: &str, episode_id: &str) -> Result<String, Error> {
+            Ok(format!("https://{}.example/watch/{}", service.to_lowercase(), episode_id))
+        }
+
+        async fn episodes(&self, service: &str, season_id: &str) -> Result<Vec<(i64, String, i64)>, Error> {
+            self.calls.lock().unwrap().push(format!("episodes {} {}", service, season_id));
+            Ok((1..=self.episode_count.max(2))
+                .map(|number| (number, format!("Chapter {}", number), 80100000 + number))
+                .collect())
+        }
+
+        async fn shahid_seasons(&self, show_id: &str) -> Result<Vec<Season>, Error> {
+            self.calls.lock().unwrap().push(format!("shahid seasons {}", show_id));
+            Ok(serde_json::from_value(json!([{ "id": 387290, "seasonNumber": 1 }, { "id": 412044, "seasonNumber": 2 }]))?)
+        }
+
+        async fn shahid_playlists(&self, season_id: &str) -> Result<Vec<Playlist>, Error> {
+            self.calls.lock().unwrap().push(format!("shahid playlists {}", season_id));
+            Ok(serde_json::from_value(json!([
+                { "id": "4992279710951949922826573713", "title": "Episodes", "type": "EPISODE" },
+                { "id": "4992279710951949922826573714", "title": "Clips", "type": "CLIP" }
+            ]))?)
+        }
+
+        async fn shahid_episodes(&self, playlist_id: &str) -> Result<Vec<Product>, Error> {
+            self.calls.lock().unwrap().push(format!("shahid episodes {}", playlist_id));
+            Ok(serde_json::from_value(json!([
+                { "id": 412050, "title": "Clip", "number": 1, "productUrl": { "url": "https://shahid.mbc.net/en/clip-412050" } }
+            ]))?)
+        }
+
+        async fn start_stream(&self, url: &str, service: &str) -> Result<(), Error> {
+            self.calls.lock().unwrap().push(format!("start {} {}", service, url));
+            if self.fail_stream {
+                return Err("chromedriver is not running".into());
+            }
+            Ok(())
+        }
+
+        async fn control(&self, action: ControlAction) -> Result<(), Error> {
+            self.calls.lock().unwrap().push(format!("control {:?}", action));
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingSink {
+        views: Mutex<Vec<View>>,
+    }
+
+    #[async_trait]
+    impl ViewSink for RecordingSink {
+        async fn show(&self, view: View) -> Result<(), Error> {
+            self.views.lock().unwrap().push(view);
+            Ok(())
+        }
+    }
+
+    impl RecordingSink {
+        fn titles(&self) -> Vec<String> {
+            self.views.lock().unwrap().iter().map(|view| view.title.clone()).collect()
+        }
+    }
+
+    fn movie(is_show: bool) -> (String, String, String, ShowResult) {
+        let show_data = if is_show {
+            json!({ "videoId": 70131314, "seasons": { "edges": [
+                { "node": { "title": "Season 1", "videoId": 80100000 } },
+                { "node": { "title": "Season 2", "videoId": 80200000 } }
+            ] } })
+        } else {
+            json!({ "movie_id": "70131314" })
+        };
+        ("https://netflix.com/watch/70131314".to_owned(), String::new(), "Dark".to_owned(), ShowResult { show_data, is_show })
+    }
+
+    #[test]
+    fn parses_component_ids() {
+        assert_eq!(
+            InteractionEvent::parse("movieSelect:Netflix", &["Dark".to_owned()]),
+            Some(InteractionEvent::MovieSelected { service: "Netflix".to_owned(), title: "Dark".to_owned() })
+        );
+        assert_eq!(InteractionEvent::parse("seasonSelect", &[]), None);
+        assert_eq!(InteractionEvent::parse("skipback", &[]), Some(InteractionEvent::Control(ControlAction::SkipBack)));
+        assert_eq!(
+            InteractionEvent::parse("episodePage:80100000:2", &[]),
+            Some(InteractionEvent::EpisodePage { service: "Netflix".to_owned(), season_id: "80100000".to_owned(), page: 2 })
+        );
+        assert_eq!(
+            InteractionEvent::parse("episodeSelect:Fasel", &["262515:3".to_owned()]),
+            Some(InteractionEvent::EpisodeSelected {
+                service: "Fasel".to_owned(),
+                season_id: "262515".to_owned(),
+                episode_id: "3".to_owned(),
+            })
+        );
+        assert_eq!(
+            InteractionEvent::parse("seasonPage:Fasel:262510:1", &[]),
+            Some(InteractionEvent::SeasonPage { service: "Fasel".to_owned(), show_id: "262510".to_owned(), page: 1 })
+        );
+        assert_eq!(
+            InteractionEvent::parse("shahidPlaylistPage:412044:1", &[]),
+            Some(InteractionEvent::ShahidPlaylistPage { season_id: "412044".to_owned(), page: 1 })
+        );
+        assert_eq!(InteractionEvent::parse("movieSelect", &["Dark".to_owned()]), None);
+        assert_eq!(InteractionEvent::parse("seasonPage:70131314:x", &[]), None);
+        assert_eq!(InteractionEvent::parse("somethingElse", &[]), None);
+    }
+
+    #[tokio::test]
+    async fn movie_goes_straight_to_the_controller() {
+        let backend = Arc::new(FakeBackend { results: vec![movie(false)], ..Default::default() });
+        let sink = RecordingSink::default();
+        let event = InteractionEvent::MovieSelected { service: "Netflix".to_owned(), title: "Dark".to_owned() };
+        Flow::new(backend.clone()).handle(event, &sink).await.unwrap();
+
+        assert_eq!(sink.titles(), vec!["Loading...", "Please Wait for the Controller", "Control the movie"]);
+        assert_eq!(*backend.calls.lock().unwrap(), vec![
+            "search Netflix Dark",
+            "start Netflix https://netflix.com/watch/70131314",
+        ]);
+        assert_eq!(sink.views.lock().unwrap().last().unwrap(), &View::controller());
+    }
+
+    #[tokio::test]
+    async fn show_walks_through_seasons_and_episodes() {
+        let backend = Arc::new(FakeBackend { results: vec![movie(true)], ..Default::default() });
+        let flow = Flow::new(backend.clone());
+        let sink = RecordingSink::default();
+
+        let event = InteractionEvent::MovieSelected { service: "Netflix".to_owned(), title: "Dark".to_owned() };
+        flow.handle(event, &sink).await.unwrap();
+        let season_view = sink.views.lock().unwrap().last().unwrap().clone();
+        let Component::Select(menu) = &season_view.components[0] else { panic!("expected a select menu") };
+        assert_eq!(menu.custom_id, "seasonSelect:Netflix");
+        assert_eq!(menu.options[1].value, "80200000");
+
+        flow.handle(InteractionEvent::parse(&menu.custom_id, &[menu.options[1].value.clone()]).unwrap(), &sink).await.unwrap();
+        let episode_view = sink.views.lock().unwrap().last().unwrap().clone();
+        let Component::Select(menu) = &episode_view.components[0] else { panic!("expected a select menu") };
+        assert_eq!(menu.options[0].label, "Episode 1 - Chapter 1");
+        assert_eq!(menu.options[1].value, "80200000:80100002");
+
+        flow.handle(InteractionEvent::parse(&menu.custom_id, &[menu.options[1].value.clone()]).unwrap(), &sink).await.unwrap();
+        assert_eq!(sink.titles().last().unwrap(), "Control the movie");
+        assert_eq!(backend.calls.lock().unwrap().last().unwrap(), "start Netflix https://netflix.example/watch/80100002");
+    }
+
+    #[tokio::test]
+    async fn shahid_shows_go_through_seasons_and_playlists() {
+        let show = ShowResult { show_data: json!({ "id": "387285" }), is_show: true };
+        let backend = Arc::new(FakeBackend {
+            results: vec![(String::new(), String::new(), "Kamel El Adad".to_owned(), show)],
+            ..Default::default()
+        });
+        let flow = Flow::new(backend.clone());
+        let sink = RecordingSink::default();
+        let pick = |custom_id: &str, value: &str| InteractionEvent::parse(custom_id, &[value.to_owned()]).unwrap();
+
+        let event = InteractionEvent::MovieSelected { service: "Shahid".to_owned(), title: "Kamel El Adad".to_owned() };
+        flow.handle(event, &sink).await.unwrap();
+        let seasons = sink.views.lock().unwrap().last().unwrap().clone();
+        let Component::Select(menu) = &seasons.components[0] else { panic!("expected a select menu") };
+        assert_eq!(menu.options[1].label, "Season 2");
+
+        flow.handle(pick(&menu.custom_id, &menu.options[1].value), &sink).await.unwrap();
+        let playlists = sink.views.lock().unwrap().last().unwrap().clone();
+        let Component::Select(menu) = &playlists.components[0] else { panic!("expected a select menu") };
+        assert_eq!(menu.options[1].label, "Clips");
+
+        flow.handle(pick(&menu.custom_id, &menu.options[1].value), &sink).await.unwrap();
+        let episodes = sink.views.lock().unwrap().last().unwrap().clone();
+        let Component::Select(menu) = &episodes.components[0] else { panic!("expected a select menu") };
+        assert_eq!(menu.options[0].value, "4992279710951949922826573714:412050");
+
+        flow.handle(pick(&menu.custom_id, &menu.options[0].value), &sink).await.unwrap();
+        assert_eq!(sink.titles().last().unwrap(), "Control the movie");
+        assert_eq!(*backend.calls.lock().unwrap(), vec![
+            "search Shahid Kamel El Adad",
+            "shahid seasons 387285",
+            "shahid playlists 412044",
+            "shahid episodes 4992279710951949922826573714",
+            "shahid episodes 4992279710951949922826573714",
+            "start Shahid https://shahid.mbc.net/en/clip-412050",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn page_buttons_reach_later_episodes() {
+        let backend = Arc::new(FakeBackend { episode_count: 60, ..Default::default() });
+        let flow = Flow::new(backend.clone());
+        let sink = RecordingSink::default();
+
+        let event = InteractionEvent::SeasonSelected { service: "Netflix".to_owned(), season_id: "80100000".to_owned() };
+        flow.handle(event, &sink).await.unwrap();
+        let first = sink.views.lock().unwrap().last().unwrap().clone();
+        let Component::Buttons(buttons) = &first.components[1] else { panic!("expected page buttons") };
+        let next = InteractionEvent::parse(&buttons[0].custom_id, &[]).unwrap();
+
+        flow.handle(next, &sink).await.unwrap();
+        let second = sink.views.lock().unwrap().last().unwrap().clone();
+        let Component::Select(menu) = &second.components[0] else { panic!("expected a select menu") };
+        assert_eq!(menu.options[0].label, "Episode 26 - Chapter 26");
+
+        let event = InteractionEvent::SeasonPage { service: "Netflix".to_owned(), show_id: "70131314".to_owned(), page: 0 };
+        flow.handle(event, &sink).await.unwrap();
+        assert_eq!(sink.titles().last().unwrap(), "Select a Season");
+        assert_eq!(backend.calls.lock().unwrap().last().unwrap(), "show Netflix 70131314");
+    }
+
+    #[tokio::test]
+    async fn failures_are_shown_to_the_user() {
+        let backend = Arc::new(FakeBackend { results: vec![movie(false)], fail_stream: true, ..Default::default() });
+        let sink = RecordingSink::default();
+        let event = InteractionEvent::MovieSelected { service: "Netflix".to_owned(), title: "Dark".to_owned() };
+        Flow::new(backend).handle(event, &sink).await.unwrap();
+        assert_eq!(sink.titles().last().unwrap(), "Something went wrong");
+
+        let empty = Arc::new(FakeBackend::default());
+        let sink = RecordingSink::default();
+        let event = InteractionEvent::MovieSelected { service: "Fasel".to_owned(), title: "Nothing".to_owned() };
+        Flow::new(empty).handle(event, &sink).await.unwrap();
+        assert_eq!(sink.titles(), vec!["Loading...", "Something went wrong"]);
+    }
+
+    #[tokio::test]
+    async fn controls_reach_the_player() {
+        let backend = Arc::new(FakeBackend::default());
+        let sink = RecordingSink::default();
+        Flow::new(backend.clone()).handle(InteractionEvent::Control(ControlAction::Pause), &sink).await.unwrap();
+        assert!(sink.titles().is_empty());
+        assert_eq!(*backend.calls.lock().unwrap(), vec!["control Pause"]);
+    }
+}
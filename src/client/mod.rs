@@ -1,2 +1,6 @@
+pub mod alerts;
+pub mod bot;
 pub mod commands;
-pub mod client;
+pub mod flow;
+pub mod render;
+pub mod views;
\ No newline at end of file
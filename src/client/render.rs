@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use poise::serenity_prelude as serenity;
+use ::serenity::all::{
+    ButtonStyle, ChannelId, ComponentInteraction, ComponentInteractionDataKind, CreateActionRow, CreateButton,
+    CreateEmbed, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption, EditMessage, MessageId,
+};
+
+use crate::client::{
+    bot::Error,
+    flow::{InteractionEvent, ViewSink},
+    views::{ButtonKind, Component, View},
+};
+
+pub fn parse_event(interaction: &ComponentInteraction) -> Option<InteractionEvent> {
+    let values = match &interaction.data.kind {
+        ComponentInteractionDataKind::StringSelect { values } => values.clone(),
+        ComponentInteractionDataKind::Button => Vec::new(),
+        _ => return None,
+    };
+    InteractionEvent::parse(&interaction.data.custom_id, &values)
+}
+
+pub fn embed(view: &View) -> CreateEmbed {
+    let embed = CreateEmbed::new().title(&view.title).description(&view.description);
+    match &view.image {
+        Some(image) => embed.image(image),
+        None => embed,
+    }
+}
+
+pub fn components(view: &View) -> Vec<CreateActionRow> {
+    view.components
+        .iter()
+        .map(|component| match component {
+            Component::Select(menu) => {
+                let options = menu.options
+                    .iter()
+                    .map(|option| {
+                        let created = CreateSelectMenuOption::new(&option.label, &option.value);
+                        match &option.description {
+                            Some(description) => created.description(description.chars().take(100).collect::<String>()),
+                            None => created,
+                        }
+                    })
+                    .collect();
+                CreateActionRow::SelectMenu(
+                    CreateSelectMenu::new(&menu.custom_id, CreateSelectMenuKind::String { options })
+                        .placeholder(&menu.placeholder),
+                )
+            }
+            Component::Buttons(buttons) => CreateActionRow::Buttons(
+                buttons
+                    .iter()
+                    .map(|button| {
+                        CreateButton::new(&button.custom_id).label(&button.label).style(match button.kind {
+                            ButtonKind::Secondary => ButtonStyle::Secondary,
+                            ButtonKind::Success => ButtonStyle::Success,
+                            ButtonKind::Danger => ButtonStyle::Danger,
+                        })
+                    })
+                    .collect(),
+            ),
+        })
+        .collect()
+}
+
+/// Replaces the whole message with `view`; attachments are only kept when the view shows one.
+pub fn edit_message(view: &View) -> EditMessage {
+    let edit = EditMessage::new().content("").embed(embed(view)).components(components(view));
+    if view.image.is_some() {
+        edit
+    } else {
+        edit.remove_all_attachments()
+    }
+}
+
+/// Shows views by editing the message the interaction came from.
+pub struct MessageSink {
+    http: Arc<serenity::Http>,
+    channel_id: ChannelId,
+    message_id: MessageId,
+}
+
+impl MessageSink {
+    pub fn new(http: Arc<serenity::Http>, interaction: &ComponentInteraction) -> Self {
+        MessageSink {
+            http,
+            channel_id: interaction.channel_id,
+            message_id: interaction.message.id,
+        }
+    }
+}
+
+#[async_trait]
+impl ViewSink for MessageSink {
+    async fn show(&self, view: View) -> Result<(), Error> {
+        self.channel_id.edit_message(&self.http, self.message_id, edit_message(&view)).await?;
+        Ok(())
+    }
+}
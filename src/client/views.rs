@@ -0,0 +1,365 @@
+use crate::server::classes::{netflix::ShowResult, shahid::client::{Playlist, Product, Season}, TitleDetails};
+
+/// Discord rejects select menus with more options than this.
+pub const MENU_LIMIT: usize = 25;
+
+/// What the bot wants the interaction message to look like, independent of Discord.
+#[derive(Debug, Clone, PartialEq)]
+pub struct View {
+    pub title: String,
+    pub description: String,
+    pub image: Option<String>,
+    pub components: Vec<Component>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Component {
+    Select(SelectMenu),
+    Buttons(Vec<Button>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectMenu {
+    pub custom_id: String,
+    pub placeholder: String,
+    pub options: Vec<SelectOption>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectOption {
+    pub label: String,
+    pub value: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonKind {
+    Secondary,
+    Success,
+    Danger,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Button {
+    pub custom_id: String,
+    pub label: String,
+    pub kind: ButtonKind,
+}
+
+impl Button {
+    fn new(custom_id: &str, label: &str, kind: ButtonKind) -> Self {
+        Button {
+            custom_id: custom_id.to_owned(),
+            label: label.to_owned(),
+            kind,
+        }
+    }
+}
+
+impl View {
+    fn new(title: &str, description: &str) -> Self {
+        View {
+            title: title.to_owned(),
+            description: description.to_owned(),
+            image: None,
+            components: Vec::new(),
+        }
+    }
+
+    pub fn search_results(
+        query: &str,
+        service: &str,
+        results: &[(String, String, String, ShowResult)],
+        details: &[Option<TitleDetails>],
+    ) -> Self {
+        let options = results
+            .iter()
+            .enumerate()
+            .map(|(index, (_, _, title, _))| SelectOption {
+                label: title.clone(),
+                value: title.clone(),
+                description: details.get(index).cloned().flatten().map(|details| details.summary()).filter(|s| !s.is_empty()),
+            })
+            .collect();
+        View {
+            image: Some("attachment://movies.png".to_owned()),
+            components: vec![Component::Select(SelectMenu {
+                custom_id: format!("movieSelect:{}", service),
+                placeholder: "Select a movie".to_owned(),
+                options,
+            })],
+            ..View::new(&format!("Here are movies for '{}'", query), &format!("Select a movie to watch on {}", service))
+        }
+    }
+
+    pub fn loading() -> Self {
+        View::new("Loading...", "Please wait while we fetch the movie.")
+    }
+
+    pub fn season_select(service: &str, show: &ShowResult, page: usize) -> Self {
+        let mut options = Vec::new();
+        if let Some(seasons) = show.show_data.get("seasons")
+            .and_then(|s| s.get("edges"))
+            .and_then(|e| e.as_array())
+        {
+            for season in seasons {
+                if let Some(season_node) = season.get("node") {
+                    let season_title = season_node.get("title")
+                        .and_then(|t| t.as_str())
+                        .unwrap_or("Unknown Season");
+                    let season_id = season_node.get("videoId")
+                        .and_then(|id| id.as_u64())
+                        .map(|id| id.to_string())
+                        .unwrap_or_else(|| "Unknown".to_owned());
+                    options.push(SelectOption { label: season_title.to_owned(), value: season_id, description: None });
+                }
+            }
+        }
+        let show_id = show.show_data.get("videoId").and_then(|id| id.as_u64()).unwrap_or_default();
+        View {
+            components: paged_select(
+                &format!("seasonSelect:{}", service),
+                "Select a season",
+                "Seasons",
+                options,
+                &format!("seasonPage:{}:{}", service, show_id),
+                page,
+            ),
+            ..View::new("Select a Season", "Choose an season from the dropdown menu.")
+        }
+    }
+
+    /// Option values are `{season_id}:{episode_id}`, as some services need the season to find the episode.
+    pub fn episode_select(service: &str, season_id: &str, episodes: &[(i64, String, i64)], page: usize) -> Self {
+        let options = episodes
+            .iter()
+            .map(|(number, title, video_id)| SelectOption {
+                label: format!("Episode {} - {}", number, title),
+                value: format!("{}:{}", season_id, video_id),
+                description: None,
+            })
+            .collect();
+        View {
+            components: paged_select(
+                &format!("episodeSelect:{}", service),
+                "Select an episode",
+                "Episodes",
+                options,
+                &format!("episodePage:{}:{}", service, season_id),
+                page,
+            ),
+            ..View::new("Select an Episode", "Choose an episode from the dropdown menu.")
+        }
+    }
+
+    pub fn shahid_season_select(show_id: &str, seasons: &[Season], page: usize) -> Self {
+        let options = seasons
+            .iter()
+            .map(|season| SelectOption { label: season.label(), value: season.id.clone(), description: None })
+            .collect();
+        View {
+            components: paged_select("shahidSeasonSelect", "Select a season", "Seasons", options, &format!("shahidSeasonPage:{}", show_id), page),
+            ..View::new("Select a Season", "Choose an season from the dropdown menu.")
+        }
+    }
+
+    /// Episodes, clips, trailers and whatever else Shahid groups a season into.
+    pub fn playlist_select(season_id: &str, playlists: &[Playlist], page: usize) -> Self {
+        let options = playlists
+            .iter()
+            .map(|playlist| SelectOption {
+                label: playlist.title.clone().or_else(|| playlist.playlist_type.clone()).unwrap_or_else(|| "Playlist".to_owned()),
+                value: playlist.id.clone(),
+                description: None,
+            })
+            .collect();
+        View {
+            components: paged_select(
+                "shahidPlaylistSelect",
+                "Select a playlist",
+                "Playlists",
+                options,
+                &format!("shahidPlaylistPage:{}", season_id),
+                page,
+            ),
+            ..View::new("Select a Playlist", "Choose what to watch from this season.")
+        }
+    }
+
+    /// Option values are `{playlist_id}:{episode_id}` so the pick can be looked up again.
+    pub fn shahid_episode_select(playlist_id: &str, episodes: &[Product], page: usize) -> Self {
+        let options = episodes
+            .iter()
+            .map(|episode| SelectOption {
+                label: match episode.number {
+                    Some(number) => format!("Episode {} - {}", number, episode.title),
+                    None => episode.title.clone(),
+                },
+                value: format!("{}:{}", playlist_id, episode.id),
+                description: None,
+            })
+            .collect();
+        View {
+            components: paged_select(
+                "shahidEpisodeSelect",
+                "Select an episode",
+                "Episodes",
+                options,
+                &format!("shahidEpisodePage:{}", playlist_id),
+                page,
+            ),
+            ..View::new("Select an Episode", "Choose an episode from the dropdown menu.")
+        }
+    }
+
+    pub fn title_details(details: &TitleDetails, image: &str) -> Self {
+        let mut lines = Vec::new();
+        if let Some(synopsis) = &details.synopsis {
+            lines.push(synopsis.clone());
+            lines.push(String::new());
+        }
+        let summary = details.summary();
+        if !summary.is_empty() {
+            lines.push(format!("**{}**", summary));
+        }
+        if details.genres.len() > 1 {
+            lines.push(format!("Genres: {}", details.genres.join(", ")));
+        }
+        if !details.cast.is_empty() {
+            lines.push(format!("Starring: {}", details.cast.join(", ")));
+        }
+        View {
+            image: Some(image.to_owned()).filter(|image| !image.is_empty()),
+            ..View::new(&details.title, &lines.join("\n"))
+        }
+    }
+
+    pub fn waiting_for_controller() -> Self {
+        View::new(
+            "Please Wait for the Controller",
+            "The Controller will start once the movie/show has started, Please sit tight till that happens.",
+        )
+    }
+
+    pub fn controller() -> Self {
+        View {
+            components: vec![Component::Buttons(vec![
+                Button::new("skipback", "⏪", ButtonKind::Secondary),
+                Button::new("pause", "⏸️", ButtonKind::Success),
+                Button::new("skipfront", "⏩", ButtonKind::Secondary),
+                Button::new("stop", "STOP", ButtonKind::Danger),
+            ])],
+            ..View::new("Control the movie", "Controller")
+        }
+    }
+
+    pub fn error(message: &str) -> Self {
+        View::new("Something went wrong", message)
+    }
+}
+
+/// One page of a select menu, plus Prev/Next buttons (`{page_id}:{page}`) when the
+/// options don't fit in a single menu.
+fn paged_select(
+    custom_id: &str,
+    placeholder: &str,
+    noun: &str,
+    options: Vec<SelectOption>,
+    page_id: &str,
+    page: usize,
+) -> Vec<Component> {
+    let total = options.len();
+    let pages = total.div_ceil(MENU_LIMIT).max(1);
+    let page = page.min(pages - 1);
+    let start = page * MENU_LIMIT;
+    let end = (start + MENU_LIMIT).min(total);
+
+    let placeholder = if pages > 1 {
+        format!("{} {}–{} of {}", noun, start + 1, end, total)
+    } else {
+        placeholder.to_owned()
+    };
+    let mut components = vec![Component::Select(SelectMenu {
+        custom_id: custom_id.to_owned(),
+        placeholder,
+        options: options.into_iter().skip(start).take(MENU_LIMIT).collect(),
+    })];
+
+    if pages > 1 {
+        let mut buttons = Vec::new();
+        if page > 0 {
+            buttons.push(Button::new(&format!("{}:{}", page_id, page - 1), "◀ Prev", ButtonKind::Secondary));
+        }
+        if page + 1 < pages {
+            buttons.push(Button::new(&format!("{}:{}", page_id, page + 1), "Next ▶", ButtonKind::Secondary));
+        }
+        components.push(Component::Buttons(buttons));
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episodes(count: i64) -> Vec<(i64, String, i64)> {
+        (1..=count).map(|number| (number, format!("Chapter {}", number), 80100000 + number)).collect()
+    }
+
+    #[test]
+    fn search_results_describe_titles_with_details() {
+        let result = |title: &str| (String::new(), String::new(), title.to_owned(), ShowResult { show_data: serde_json::Value::Null, is_show: false });
+        let details = TitleDetails { title: "Dark".to_owned(), year: Some(2017), seasons: Some(3), ..Default::default() };
+        let view = View::search_results("dark", "Netflix", &[result("Dark"), result("Darker")], &[Some(details), None]);
+        let Component::Select(menu) = &view.components[0] else { panic!("expected a select menu") };
+        assert_eq!(menu.options[0].description.as_deref(), Some("2017 · 3 Seasons"));
+        assert_eq!(menu.options[1].description, None);
+    }
+
+    #[test]
+    fn short_lists_fit_in_one_menu() {
+        let view = View::episode_select("Netflix", "80100000", &episodes(10), 0);
+        assert_eq!(view.components.len(), 1);
+        let Component::Select(menu) = &view.components[0] else { panic!("expected a select menu") };
+        assert_eq!(menu.options.len(), 10);
+    }
+
+    #[test]
+    fn long_seasons_page_through_every_episode() {
+        let all = episodes(60);
+        let mut seen = Vec::new();
+        for page in 0..3 {
+            let view = View::episode_select("Netflix", "80100000", &all, page);
+            let Component::Select(menu) = &view.components[0] else { panic!("expected a select menu") };
+            let Component::Buttons(buttons) = &view.components[1] else { panic!("expected page buttons") };
+            assert!(menu.options.len() <= MENU_LIMIT);
+            seen.extend(menu.options.iter().map(|option| option.value.clone()));
+
+            let ids: Vec<&str> = buttons.iter().map(|button| button.custom_id.as_str()).collect();
+            match page {
+                0 => assert_eq!(ids, vec!["episodePage:Netflix:80100000:1"]),
+                1 => assert_eq!(ids, vec!["episodePage:Netflix:80100000:0", "episodePage:Netflix:80100000:2"]),
+                _ => {
+                    assert_eq!(ids, vec!["episodePage:Netflix:80100000:1"]);
+                    assert_eq!(menu.placeholder, "Episodes 51–60 of 60");
+                }
+            }
+        }
+        assert_eq!(seen.len(), 60);
+        assert_eq!(seen.last().unwrap(), "80100000:80100060");
+    }
+
+    #[test]
+    fn long_seasons_page_through_every_playlist() {
+        let playlists: Vec<Playlist> = serde_json::from_value(serde_json::Value::Array(
+            (0..30).map(|index| serde_json::json!({ "id": index.to_string(), "title": format!("Part {}", index) })).collect(),
+        ))
+        .unwrap();
+        let view = View::playlist_select("412044", &playlists, 1);
+        let Component::Select(menu) = &view.components[0] else { panic!("expected a select menu") };
+        let Component::Buttons(buttons) = &view.components[1] else { panic!("expected page buttons") };
+        assert_eq!(menu.options.len(), 5);
+        assert_eq!(menu.options[4].value, "29");
+        assert_eq!(buttons[0].custom_id, "shahidPlaylistPage:412044:0");
+    }
+}
@@ -1,65 +1,151 @@
-use server::classes::shahid::ShahidSearcher;
-use thirtyfour::{prelude::*, ChromeCapabilities};
+use server::classes::{netflix::NetflixSearcher, scraped::SCRAPERS, shahid::ShahidSearcher, Provider};
+use server::http::{serve_fixtures, FixtureStore, HttpMode, HTTP};
+use thirtyfour::prelude::*;
 mod server;
 mod client;
-use server::functions::{start_discord, DiscordData};
+use server::{browser::{launch, BrowserBackend, WebDriverBackend}, config::CONFIG, diagnostics::{record_failure, Trace}, functions::{start_discord, DiscordData}};
+use std::sync::Arc;
 use std::fs;
 use std::env;
 struct Streamer;
 #[warn(unused_must_use)]
 impl Streamer {
-    async fn start(_url: &str, r#type: &str, _id: &str) -> WebDriverResult<()>  {
-        let exe_dir = env::current_exe().unwrap()
-        .parent().unwrap()
-        .to_path_buf();
+    async fn start(_url: &str, r#type: &str, _id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let trace = Trace::new();
+        trace.step("browser launch");
+        let options = match launch::capabilities(&CONFIG.browser) {
+            Ok(options) => options,
+            Err(err) => {
+                record_failure(None, &trace, &err.to_string()).await;
+                return Err(err.into());
+            }
+        };
 
-        println!("{:?}", exe_dir);
-        let extensions_dir = exe_dir.join("Extensions");
+        trace.step("browser session");
+        let driver = match WebDriver::new(CONFIG.browser.driver_url.as_str(), options).await {
+            Ok(driver) => driver,
+            Err(err) => {
+                record_failure(None, &trace, &err.to_string()).await;
+                return Err(err.into());
+            }
+        };
+        let backend: Arc<dyn BrowserBackend> = Arc::new(WebDriverBackend::new(driver));
+        if let Err(err) = start_discord(backend.clone(), Some(DiscordData { id: _id.to_string()}), _url, r#type, &trace).await {
+            record_failure(Some(backend.as_ref()), &trace, &err.to_string()).await;
+            return Err(err.into());
+        }
+        Ok(())
+    }
+}
 
-        let extensions: Vec<String> = fs::read_dir(extensions_dir)
-            .unwrap()
-            .filter_map(|entry| {
-                let path = entry.ok()?.path();
-                if path.is_dir() {
-                    path.to_str().map(|s| s.to_string())
-                } else {
-                    None
-                }
-            })
-            .collect();
+/// Prints whatever would stop the browser from launching. Returns false if anything would.
+async fn check_browser() -> bool {
+    let problems = launch::preflight(&CONFIG.browser).await;
+    for problem in &problems {
+        println!("Browser preflight: {}", problem);
+    }
+    problems.is_empty()
+}
+
+/// Re-records the provider fixtures by running a search (and the follow-up
+/// season/playlist lookups) for every query against the live APIs.
+async fn record_fixtures(queries: &[String]) {
+    HTTP.set_mode(HttpMode::Record);
+    println!("Recording fixtures into {:?}", HTTP.store().dir());
 
-        let extensions_arg = extensions.join(",");
-        let mut options = ChromeCapabilities::new();
-        options.add_arg("--ignore-ssl-errors=yes").unwrap();
-        options.add_arg("--ignore-certificate-errors").unwrap();
-        options.add_arg(
-            "--auto-select-tab-capture-source-by-title=about:blank"
-        ).unwrap();
-        options.add_arg("--disable-gpu").unwrap();
-        options.add_arg("--enable-chrome-browser-cloud-management").unwrap();
-        options.add_arg("--enable-javascript").unwrap();
-        options.add_arg("--disable-blink-features=AutomationControlled").unwrap();
-        options.add_arg("--auto-accept-camera-and-microphone-capture").unwrap();
-        options.add_arg("--user-agent=Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.0.0").unwrap();
-        options.add_arg("--start-maximized").unwrap();
-        options.add_arg(&format!("--load-extension={}", extensions_arg)).unwrap();
+    for query in queries {
+        match NetflixSearcher::search(query).await {
+            Ok(results) => {
+                if let Some(season_id) = results.iter()
+                    .find(|result| result.3.is_show)
+                    .and_then(|result| result.3.show_data["seasons"]["edges"][0]["node"]["videoId"].as_u64())
+                {
+                    if let Err(e) = NetflixSearcher::get_episodes_for_shows(&season_id.to_string()).await {
+                        println!("Netflix episodes for '{}' failed: {}", query, e)
+                    }
+                }
+            }
+            Err(e) => { println!("Netflix search for '{}' failed: {}", query, e) }
+        }
+        if let Err(e) = record_shahid(query).await {
+            println!("Shahid lookups for '{}' failed: {}", query, e)
+        }
+        for scraper in SCRAPERS.iter() {
+            if let Err(e) = scraper.search(query).await {
+                println!("{} search for '{}' failed: {}", scraper.name(), query, e)
+            }
+        }
+    }
+}
 
-        println!("Loading extensions from: {}", extensions_arg);
+/// Runs every scraper definition that names a `fixture` against that saved page.
+/// Returns false if any of them no longer finds results there.
+fn check_scrapers() -> bool {
+    let mut ok = true;
+    for scraper in SCRAPERS.iter() {
+        let Some(fixture) = &scraper.config().fixture else {
+            println!("{}: no fixture to check against", scraper.name());
+            continue;
+        };
+        let result = fs::read_to_string(fixture)
+            .map_err(|e| format!("Couldn't read {}: {}", fixture, e).into())
+            .and_then(|html| scraper.check(&html));
+        match result {
+            Ok(count) => println!("{}: {} results in {}", scraper.name(), count, fixture),
+            Err(e) => {
+                println!("{}", e);
+                ok = false;
+            }
+        }
+    }
+    ok
+}
 
-        let driver = WebDriver::new("http://localhost:50000", options).await?;
-        start_discord(driver, Some(DiscordData { id: _id.to_string()}), _url, r#type).await?;
-        Ok(())
+/// Search, then the first show's seasons, its first season's playlists and the first playlist.
+async fn record_shahid(query: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let results = ShahidSearcher::search(query).await?;
+    let Some(show_id) = results.iter()
+        .find(|result| result.3.is_show)
+        .and_then(|result| result.3.show_data["id"].as_str())
+    else {
+        return Ok(());
+    };
+    if let Some(season) = ShahidSearcher::seasons(show_id).await?.first() {
+        if let Some(playlist) = ShahidSearcher::playlists(&season.id).await?.first() {
+            ShahidSearcher::episodes(&playlist.id).await?;
+        }
     }
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() {
-    client::client::main().await;
-    // match ShahidSearcher::search("كامل العدد").await {
-    //     Ok(results) => {
-    //         ShahidSearcher::fetch_shahid_playlist(&results[0].1).await;
-    //     }
-    //     Err(e) => { println!("{}", e) } 
-    // }
-    
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("record-fixtures") => {
+            let queries = if args.len() > 1 { args[1..].to_vec() } else { vec!["Dark".to_string(), "كامل العدد".to_string()] };
+            record_fixtures(&queries).await;
+        }
+        Some("check-scrapers") => {
+            if !check_scrapers() {
+                std::process::exit(1);
+            }
+        }
+        Some("check-browser") => {
+            if !check_browser().await {
+                std::process::exit(1);
+            }
+        }
+        Some("serve-fixtures") => {
+            let addr = args.get(1).map(String::as_str).unwrap_or("127.0.0.1:8089");
+            if let Err(e) = serve_fixtures(FixtureStore::new(HTTP.store().dir()), addr).await {
+                println!("Fixture server failed: {}", e)
+            }
+        }
+        _ => {
+            // The bot still searches without a browser, so problems are reported rather than fatal.
+            check_browser().await;
+            client::bot::main().await
+        }
+    }
 }
\ No newline at end of file
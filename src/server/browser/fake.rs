@@ -0,0 +1,265 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use serde_json::Value;
+use thirtyfour::{
+    error::{WebDriverError, WebDriverResult},
+    Cookie, ElementRect,
+};
+
+use super::{BrowserBackend, Locator};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BrowserCall {
+    Goto(String),
+    Execute(String),
+    Click(Locator),
+    ClickAtOffset(Locator, i64, i64),
+    MoveMouse(i64, i64),
+    ClickAt(i64, i64),
+    EnterFrame(u16),
+    EnterDefaultFrame,
+    SwitchToWindow(String),
+    AddCookie(String),
+    DeleteAllCookies,
+    Screenshot,
+    Quit,
+}
+
+/// A scripted result, optionally only answered inside one frame.
+type ScriptResult = (Option<Vec<u16>>, String, Value);
+
+/// In-memory browser that records every call and answers from scripted state.
+/// Elements are present unless marked missing with `remove`.
+pub struct FakeBrowser {
+    calls: Mutex<Vec<BrowserCall>>,
+    window_size: (i64, i64),
+    windows: Mutex<Vec<String>>,
+    scripts: Mutex<Vec<ScriptResult>>,
+    frame: Mutex<Vec<u16>>,
+    missing: Mutex<HashSet<Locator>>,
+    broken: Mutex<HashSet<Locator>>,
+    attrs: Mutex<HashMap<(Locator, String), String>>,
+    rects: Mutex<HashMap<Locator, ElementRect>>,
+    cookies: Mutex<Vec<Cookie>>,
+}
+
+impl Default for FakeBrowser {
+    fn default() -> Self {
+        FakeBrowser {
+            calls: Mutex::new(Vec::new()),
+            window_size: (1920, 1080),
+            windows: Mutex::new(vec!["main".to_string()]),
+            scripts: Mutex::new(Vec::new()),
+            frame: Mutex::new(Vec::new()),
+            missing: Mutex::new(HashSet::new()),
+            broken: Mutex::new(HashSet::new()),
+            attrs: Mutex::new(HashMap::new()),
+            rects: Mutex::new(HashMap::new()),
+            cookies: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl FakeBrowser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn calls(&self) -> Vec<BrowserCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    pub fn set_windows(&self, handles: &[&str]) {
+        *self.windows.lock().unwrap() = handles.iter().map(|handle| handle.to_string()).collect();
+    }
+
+    /// Scripts containing `fragment` return `result`; everything else returns null.
+    pub fn script_result(&self, fragment: &str, result: Value) {
+        self.scripts.lock().unwrap().push((None, fragment.to_string(), result));
+    }
+
+    /// Like `script_result`, but only while inside the frame reached by entering the
+    /// `frame` indexes in turn from the top-level document. Takes precedence over
+    /// results that aren't tied to a frame.
+    pub fn script_result_in_frame(&self, frame: &[u16], fragment: &str, result: Value) {
+        self.scripts.lock().unwrap().push((Some(frame.to_vec()), fragment.to_string(), result));
+    }
+
+    pub fn remove(&self, locator: Locator) {
+        self.missing.lock().unwrap().insert(locator);
+    }
+
+    /// Makes lookups of `locator` fail, like WebDriver does for an invalid selector.
+    pub fn break_lookup(&self, locator: Locator) {
+        self.broken.lock().unwrap().insert(locator);
+    }
+
+    pub fn set_attr(&self, locator: Locator, name: &str, value: &str) {
+        self.attrs.lock().unwrap().insert((locator, name.to_string()), value.to_string());
+    }
+
+    pub fn set_rect(&self, locator: Locator, rect: ElementRect) {
+        self.rects.lock().unwrap().insert(locator, rect);
+    }
+
+    fn record(&self, call: BrowserCall) {
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn lookup(&self, locator: &Locator) -> WebDriverResult<bool> {
+        if self.broken.lock().unwrap().contains(locator) {
+            return Err(WebDriverError::NotFound(format!("{:?}", locator), "invalid selector".to_string()));
+        }
+        Ok(self.require(locator).is_ok())
+    }
+
+    fn require(&self, locator: &Locator) -> WebDriverResult<()> {
+        if self.missing.lock().unwrap().contains(locator) {
+            return Err(WebDriverError::NotFound(format!("{:?}", locator), "fake browser".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BrowserBackend for FakeBrowser {
+    async fn goto(&self, url: &str) -> WebDriverResult<()> {
+        self.record(BrowserCall::Goto(url.to_string()));
+        Ok(())
+    }
+
+    async fn execute(&self, script: &str, _args: Vec<Value>) -> WebDriverResult<Value> {
+        self.record(BrowserCall::Execute(script.to_string()));
+        let frame = self.frame.lock().unwrap().clone();
+        let scripts = self.scripts.lock().unwrap();
+        let matching = |wanted: Option<&Vec<u16>>| {
+            scripts
+                .iter()
+                .find(|(scripted, fragment, _)| scripted.as_ref() == wanted && script.contains(fragment.as_str()))
+        };
+        Ok(matching(Some(&frame))
+            .or_else(|| matching(None))
+            .map(|(_, _, result)| result.clone())
+            .unwrap_or(Value::Null))
+    }
+
+    async fn current_url(&self) -> WebDriverResult<String> {
+        let calls = self.calls.lock().unwrap();
+        Ok(calls
+            .iter()
+            .rev()
+            .find_map(|call| match call {
+                BrowserCall::Goto(url) => Some(url.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| "about:blank".to_string()))
+    }
+
+    async fn is_present(&self, locator: &Locator) -> WebDriverResult<bool> {
+        self.lookup(locator)
+    }
+
+    async fn is_clickable(&self, locator: &Locator) -> WebDriverResult<bool> {
+        self.lookup(locator)
+    }
+
+    async fn click(&self, locator: &Locator) -> WebDriverResult<()> {
+        self.require(locator)?;
+        self.record(BrowserCall::Click(locator.clone()));
+        Ok(())
+    }
+
+    async fn attr(&self, locator: &Locator, name: &str) -> WebDriverResult<Option<String>> {
+        self.require(locator)?;
+        Ok(self.attrs.lock().unwrap().get(&(locator.clone(), name.to_string())).cloned())
+    }
+
+    async fn rect(&self, locator: &Locator) -> WebDriverResult<ElementRect> {
+        self.require(locator)?;
+        Ok(self.rects.lock().unwrap().get(locator).cloned().unwrap_or(ElementRect {
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+            height: 0.0,
+        }))
+    }
+
+    async fn click_at_offset(&self, locator: &Locator, x: i64, y: i64) -> WebDriverResult<()> {
+        self.require(locator)?;
+        self.record(BrowserCall::ClickAtOffset(locator.clone(), x, y));
+        Ok(())
+    }
+
+    async fn window_size(&self) -> WebDriverResult<(i64, i64)> {
+        Ok(self.window_size)
+    }
+
+    async fn move_mouse(&self, x: i64, y: i64) -> WebDriverResult<()> {
+        self.record(BrowserCall::MoveMouse(x, y));
+        Ok(())
+    }
+
+    async fn click_at(&self, x: i64, y: i64) -> WebDriverResult<()> {
+        self.record(BrowserCall::ClickAt(x, y));
+        Ok(())
+    }
+
+    async fn enter_frame(&self, index: u16) -> WebDriverResult<()> {
+        self.record(BrowserCall::EnterFrame(index));
+        self.frame.lock().unwrap().push(index);
+        Ok(())
+    }
+
+    async fn enter_default_frame(&self) -> WebDriverResult<()> {
+        self.record(BrowserCall::EnterDefaultFrame);
+        self.frame.lock().unwrap().clear();
+        Ok(())
+    }
+
+    async fn windows(&self) -> WebDriverResult<Vec<String>> {
+        Ok(self.windows.lock().unwrap().clone())
+    }
+
+    async fn switch_to_window(&self, handle: &str) -> WebDriverResult<()> {
+        self.record(BrowserCall::SwitchToWindow(handle.to_string()));
+        Ok(())
+    }
+
+    async fn get_all_cookies(&self) -> WebDriverResult<Vec<Cookie>> {
+        Ok(self.cookies.lock().unwrap().clone())
+    }
+
+    async fn add_cookie(&self, cookie: Cookie) -> WebDriverResult<()> {
+        self.record(BrowserCall::AddCookie(cookie.name.clone()));
+        self.cookies.lock().unwrap().push(cookie);
+        Ok(())
+    }
+
+    async fn delete_all_cookies(&self) -> WebDriverResult<()> {
+        self.record(BrowserCall::DeleteAllCookies);
+        self.cookies.lock().unwrap().clear();
+        Ok(())
+    }
+
+    async fn screenshot(&self) -> WebDriverResult<Vec<u8>> {
+        self.record(BrowserCall::Screenshot);
+        Ok(Vec::new())
+    }
+
+    async fn page_source(&self) -> WebDriverResult<String> {
+        Ok("<html><body></body></html>".to_string())
+    }
+
+    async fn console_logs(&self) -> WebDriverResult<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    async fn quit(&self) -> WebDriverResult<()> {
+        self.record(BrowserCall::Quit);
+        Ok(())
+    }
+}
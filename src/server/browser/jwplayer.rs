@@ -0,0 +1,242 @@
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thirtyfour::error::{WebDriverError, WebDriverResult};
+
+use super::{selectors::SELECTORS, wait::until, BrowserBackend};
+
+/// Embeds rarely go deeper than the site page, a host's wrapper page and the player itself.
+const MAX_FRAME_DEPTH: usize = 3;
+
+const PROBE_SCRIPT: &str = r#"
+    const player = typeof jwplayer === 'function' && jwplayer();
+    return { player: !!player && typeof player.getState === 'function', frames: window.frames.length };
+"#;
+
+const STATE_SCRIPT: &str = r#"
+    const player = jwplayer();
+    const finite = (value) => (Number.isFinite(value) ? value : 0);
+    return {
+        state: player.getState(),
+        position: finite(player.getPosition()),
+        duration: finite(player.getDuration()),
+        volume: finite(player.getVolume()),
+        quality: player.getCurrentQuality(),
+        qualities: (player.getQualityLevels() || []).map((level) => level.label),
+        captions: player.getCurrentCaptions(),
+        caption_tracks: (player.getCaptionsList() || []).map((track) => track.label),
+    };
+"#;
+
+#[derive(Deserialize)]
+struct FrameProbe {
+    player: bool,
+    frames: u16,
+}
+
+/// What `jwplayer()` reports after every command.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct JwState {
+    /// `idle`, `buffering`, `playing`, `paused` or `complete`.
+    pub state: String,
+    pub position: f64,
+    pub duration: f64,
+    pub volume: f64,
+    /// Index into `qualities`, -1 when the stream has a single level.
+    pub quality: i64,
+    #[serde(default)]
+    pub qualities: Vec<String>,
+    /// Index into `caption_tracks`, 0 being "Off".
+    pub captions: i64,
+    #[serde(default)]
+    pub caption_tracks: Vec<String>,
+}
+
+impl JwState {
+    pub fn quality_label(&self) -> Option<&str> {
+        usize::try_from(self.quality).ok().and_then(|index| self.qualities.get(index)).map(String::as_str)
+    }
+
+    pub fn caption_label(&self) -> Option<&str> {
+        usize::try_from(self.captions).ok().and_then(|index| self.caption_tracks.get(index)).map(String::as_str)
+    }
+}
+
+/// Drives any page that embeds JW Player through its `jwplayer()` JavaScript API,
+/// wherever in the page's iframes the player lives.
+pub struct JwPlayer {
+    driver: Arc<dyn BrowserBackend>,
+}
+
+impl JwPlayer {
+    pub fn new(driver: Arc<dyn BrowserBackend>) -> Self {
+        JwPlayer { driver }
+    }
+
+    /// Polls for the player until `timeout`, for pages that load their embeds late.
+    pub async fn wait(&self, timeout: Duration) -> WebDriverResult<()> {
+        until("JW Player", timeout, || async { self.locate().await.map(|()| true) }).await
+    }
+
+    pub async fn state(&self) -> WebDriverResult<JwState> {
+        self.run("", Vec::new()).await
+    }
+
+    pub async fn play(&self) -> WebDriverResult<JwState> {
+        self.run("jwplayer().play();", Vec::new()).await
+    }
+
+    pub async fn pause(&self) -> WebDriverResult<JwState> {
+        self.run("jwplayer().pause();", Vec::new()).await
+    }
+
+    /// Pauses a playing stream and plays anything else.
+    pub async fn toggle(&self) -> WebDriverResult<JwState> {
+        self.run("jwplayer().getState() === 'playing' ? jwplayer().pause() : jwplayer().play();", Vec::new()).await
+    }
+
+    pub async fn seek(&self, seconds: f64) -> WebDriverResult<JwState> {
+        self.run("jwplayer().seek(Math.max(0, arguments[0]));", vec![json!(seconds)]).await
+    }
+
+    pub async fn seek_by(&self, seconds: f64) -> WebDriverResult<JwState> {
+        self.run("jwplayer().seek(Math.max(0, jwplayer().getPosition() + arguments[0]));", vec![json!(seconds)]).await
+    }
+
+    /// `volume` is a percentage and is capped at 100.
+    pub async fn set_volume(&self, volume: u8) -> WebDriverResult<JwState> {
+        self.run("jwplayer().setVolume(arguments[0]);", vec![json!(volume.min(100))]).await
+    }
+
+    /// Switches to the quality level labelled `label`, such as `720p` or `Auto`.
+    pub async fn set_quality(&self, label: &str) -> WebDriverResult<JwState> {
+        let state = self.state().await?;
+        let index = find_label(&state.qualities, label, "quality")?;
+        self.run("jwplayer().setCurrentQuality(arguments[0]);", vec![json!(index)]).await
+    }
+
+    /// Picks the captions track labelled `label`; most players list an `Off` track first.
+    pub async fn set_captions(&self, label: &str) -> WebDriverResult<JwState> {
+        let state = self.state().await?;
+        let index = find_label(&state.caption_tracks, label, "captions")?;
+        self.run("jwplayer().setCurrentCaptions(arguments[0]);", vec![json!(index)]).await
+    }
+
+    /// Clicks the fullscreen button, as browsers only allow fullscreen from a user gesture.
+    pub async fn fullscreen(&self) -> WebDriverResult<()> {
+        self.locate().await?;
+        SELECTORS.click(self.driver.as_ref(), "jwplayer.fullscreen", &[]).await
+    }
+
+    async fn run(&self, action: &str, args: Vec<Value>) -> WebDriverResult<JwState> {
+        self.locate().await?;
+        let value = self.driver.execute(&format!("{}\n{}", action, STATE_SCRIPT), args).await?;
+        serde_json::from_value(value)
+            .map_err(|err| WebDriverError::NotFound("JW Player state".to_string(), err.to_string()))
+    }
+
+    /// Leaves the driver inside the frame that has the player. The current frame is
+    /// tried first, so only the first command after a page load walks the frames.
+    async fn locate(&self) -> WebDriverResult<()> {
+        if self.probe().await?.player {
+            return Ok(());
+        }
+        let mut queue = VecDeque::from([Vec::new()]);
+        while let Some(path) = queue.pop_front() {
+            self.driver.enter_default_frame().await?;
+            for index in &path {
+                self.driver.enter_frame(*index).await?;
+            }
+            let probe = self.probe().await?;
+            if probe.player {
+                return Ok(());
+            }
+            if path.len() < MAX_FRAME_DEPTH {
+                queue.extend((0..probe.frames).map(|index| [path.as_slice(), &[index]].concat()));
+            }
+        }
+        self.driver.enter_default_frame().await?;
+        Err(WebDriverError::NotFound(
+            "JW Player".to_string(),
+            "no frame on the page has jwplayer()".to_string(),
+        ))
+    }
+
+    async fn probe(&self) -> WebDriverResult<FrameProbe> {
+        let value = self.driver.execute(PROBE_SCRIPT, Vec::new()).await?;
+        Ok(serde_json::from_value(value).unwrap_or(FrameProbe { player: false, frames: 0 }))
+    }
+}
+
+fn find_label(labels: &[String], wanted: &str, what: &str) -> WebDriverResult<usize> {
+    labels
+        .iter()
+        .position(|label| label.trim().eq_ignore_ascii_case(wanted.trim()))
+        .ok_or_else(|| WebDriverError::NotFound(
+            format!("JW Player {} '{}'", what, wanted),
+            format!("the player offers {}", labels.join(", ")),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::browser::fake::{BrowserCall, FakeBrowser};
+
+    fn state() -> Value {
+        json!({
+            "state": "playing", "position": 61.5, "duration": 5400, "volume": 100,
+            "quality": 1, "qualities": ["Auto", "1080p", "720p"],
+            "captions": 0, "caption_tracks": ["Off", "Arabic", "English"],
+        })
+    }
+
+    #[tokio::test]
+    async fn finds_the_player_two_frames_down() {
+        let browser = Arc::new(FakeBrowser::new());
+        browser.script_result_in_frame(&[], "window.frames.length", json!({ "player": false, "frames": 2 }));
+        browser.script_result_in_frame(&[1], "window.frames.length", json!({ "player": false, "frames": 1 }));
+        browser.script_result_in_frame(&[1, 0], "window.frames.length", json!({ "player": true, "frames": 0 }));
+        browser.script_result_in_frame(&[1, 0], "getCaptionsList", state());
+
+        let state = JwPlayer::new(browser.clone()).pause().await.unwrap();
+        assert_eq!(state.quality_label(), Some("1080p"));
+        assert_eq!(state.caption_label(), Some("Off"));
+
+        let calls = browser.calls();
+        let last_default = calls.iter().rposition(|call| *call == BrowserCall::EnterDefaultFrame).unwrap();
+        assert_eq!(calls[last_default + 1..last_default + 3], [BrowserCall::EnterFrame(1), BrowserCall::EnterFrame(0)]);
+        assert!(matches!(calls.last(), Some(BrowserCall::Execute(script)) if script.starts_with("jwplayer().pause();")));
+    }
+
+    #[tokio::test]
+    async fn stays_in_the_current_frame_once_found() {
+        let browser = Arc::new(FakeBrowser::new());
+        browser.script_result("window.frames.length", json!({ "player": true, "frames": 0 }));
+        browser.script_result("getCaptionsList", state());
+
+        JwPlayer::new(browser.clone()).seek_by(10.0).await.unwrap();
+        assert!(!browser.calls().iter().any(|call| matches!(call, BrowserCall::EnterFrame(_) | BrowserCall::EnterDefaultFrame)));
+    }
+
+    #[tokio::test]
+    async fn fails_without_a_player() {
+        let browser = Arc::new(FakeBrowser::new());
+        browser.script_result_in_frame(&[], "window.frames.length", json!({ "player": false, "frames": 1 }));
+        assert!(JwPlayer::new(browser).play().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn picks_quality_and_captions_by_label() {
+        let browser = Arc::new(FakeBrowser::new());
+        browser.script_result("window.frames.length", json!({ "player": true, "frames": 0 }));
+        browser.script_result("getCaptionsList", state());
+        let player = JwPlayer::new(browser.clone());
+
+        player.set_quality("720P").await.unwrap();
+        assert!(matches!(browser.calls().last(), Some(BrowserCall::Execute(script)) if script.contains("setCurrentQuality")));
+        let err = player.set_captions("French").await.unwrap_err().to_string();
+        assert!(err.contains("Off, Arabic, English"), "{}", err);
+    }
+}
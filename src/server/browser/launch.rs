@@ -0,0 +1,326 @@
+use std::{
+    env, fmt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde_json::Value;
+use thirtyfour::{error::WebDriverError, prelude::*, ChromeCapabilities};
+
+use crate::server::config::BrowserConfig;
+
+/// Switches the stream can't work without: Discord captures the `about:blank` tab
+/// and the capture prompt has to be accepted without anyone clicking it.
+const REQUIRED_ARGS: [&str; 2] = [
+    "--auto-select-tab-capture-source-by-title=about:blank",
+    "--auto-accept-camera-and-microphone-capture",
+];
+
+const DRIVER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Executables looked up on `PATH` when no `browser.binary` is configured.
+const BROWSER_NAMES: [&str; 6] = ["google-chrome", "google-chrome-stable", "chromium", "chromium-browser", "chrome", "msedge"];
+
+/// Default install locations on Windows and macOS, where browsers usually aren't on `PATH`.
+const BROWSER_PATHS: [&str; 5] = [
+    r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+    r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+    r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+];
+
+#[derive(Debug)]
+pub enum LaunchError {
+    /// Nothing answered at the driver url, or it answered with something else.
+    Driver { url: String, reason: String },
+    /// The driver is up but says it can't start sessions.
+    DriverNotReady { url: String, message: String },
+    MissingBinary(String),
+    NoBrowser,
+    MissingExtensions(String),
+    /// The path exists but neither is an unpacked extension nor holds any.
+    NotAnExtension(String),
+    ProfileNotADir(String),
+    Capabilities(WebDriverError),
+}
+
+impl fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaunchError::Driver { url, reason } => write!(
+                f,
+                "No WebDriver at {} ({}); start chromedriver on that port or set browser.driver_url",
+                url, reason
+            ),
+            LaunchError::DriverNotReady { url, message } => write!(f, "The WebDriver at {} isn't ready: {}", url, message),
+            LaunchError::MissingBinary(path) => write!(f, "browser.binary {} doesn't exist", path),
+            LaunchError::NoBrowser => write!(f, "Couldn't find Chrome or Edge; install one or set browser.binary"),
+            LaunchError::MissingExtensions(path) => write!(f, "Extensions folder {} doesn't exist", path),
+            LaunchError::NotAnExtension(path) => {
+                write!(f, "{} has no manifest.json and no unpacked extensions in it", path)
+            }
+            LaunchError::ProfileNotADir(path) => write!(f, "browser.profile_dir {} isn't a folder", path),
+            LaunchError::Capabilities(err) => write!(f, "Couldn't build the browser options: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for LaunchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LaunchError::Capabilities(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// The browser options for `config`, failing on extensions or a binary that aren't there.
+pub fn capabilities(config: &BrowserConfig) -> Result<ChromeCapabilities, LaunchError> {
+    let extensions = extensions(config)?;
+    let mut options = ChromeCapabilities::new();
+    if let Some(binary) = &config.binary {
+        if !Path::new(binary).is_file() {
+            return Err(LaunchError::MissingBinary(binary.clone()));
+        }
+        options.set_binary(binary).map_err(LaunchError::Capabilities)?;
+    }
+    for arg in args(config, &extensions) {
+        options.add_arg(&arg).map_err(LaunchError::Capabilities)?;
+    }
+    options
+        .set_base_capability("goog:loggingPrefs", serde_json::json!({ "browser": "ALL" }))
+        .map_err(LaunchError::Capabilities)?;
+    Ok(options)
+}
+
+fn args(config: &BrowserConfig, extensions: &[PathBuf]) -> Vec<String> {
+    let mut args: Vec<String> = REQUIRED_ARGS.iter().map(|arg| arg.to_string()).collect();
+    args.extend(config.args.iter().cloned());
+    if config.headless {
+        // The new headless mode is the full browser, so extensions and tab capture still work.
+        args.push("--headless=new".to_string());
+    }
+    match config.window_size {
+        Some([width, height]) => args.push(format!("--window-size={},{}", width, height)),
+        None => args.push("--start-maximized".to_string()),
+    }
+    if let Some(profile) = &config.profile_dir {
+        args.push(format!("--user-data-dir={}", profile));
+    }
+    if !extensions.is_empty() {
+        let paths: Vec<String> = extensions.iter().map(|path| path.to_string_lossy().to_string()).collect();
+        args.push(format!("--load-extension={}", paths.join(",")));
+    }
+    args
+}
+
+/// The configured extensions, which have to be there, or else whatever the optional
+/// `Extensions` folder next to the executable holds.
+fn extensions(config: &BrowserConfig) -> Result<Vec<PathBuf>, LaunchError> {
+    if !config.extensions.is_empty() {
+        return extension_dirs(&config.extensions);
+    }
+    let bundled = env::current_exe().ok().and_then(|exe| Some(exe.parent()?.join("Extensions")));
+    Ok(bundled
+        .filter(|dir| dir.is_dir())
+        .and_then(|dir| extension_dirs(&[dir.to_string_lossy().to_string()]).ok())
+        .unwrap_or_default())
+}
+
+/// Expands each configured path into unpacked extensions: the path itself when it has
+/// a `manifest.json`, otherwise the folders inside it that do.
+pub fn extension_dirs(paths: &[String]) -> Result<Vec<PathBuf>, LaunchError> {
+    let mut dirs = Vec::new();
+    for path in paths {
+        let root = Path::new(path);
+        if !root.is_dir() {
+            return Err(LaunchError::MissingExtensions(path.clone()));
+        }
+        if root.join("manifest.json").is_file() {
+            dirs.push(root.to_path_buf());
+            continue;
+        }
+        let entries = std::fs::read_dir(root).map_err(|_| LaunchError::MissingExtensions(path.clone()))?;
+        let mut found: Vec<PathBuf> = entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|dir| dir.join("manifest.json").is_file())
+            .collect();
+        if found.is_empty() {
+            return Err(LaunchError::NotAnExtension(path.clone()));
+        }
+        found.sort();
+        dirs.extend(found);
+    }
+    Ok(dirs)
+}
+
+/// Everything that would stop the browser from launching, as readable errors.
+pub async fn preflight(config: &BrowserConfig) -> Vec<LaunchError> {
+    let mut problems = Vec::new();
+    if let Err(err) = check_driver(&config.driver_url).await {
+        problems.push(err);
+    }
+    match &config.binary {
+        Some(binary) if !Path::new(binary).is_file() => problems.push(LaunchError::MissingBinary(binary.clone())),
+        Some(_) => {}
+        None if find_browser().is_none() => problems.push(LaunchError::NoBrowser),
+        None => {}
+    }
+    if let Err(err) = extensions(config) {
+        problems.push(err);
+    }
+    if let Some(profile) = &config.profile_dir {
+        let path = Path::new(profile);
+        if path.exists() && !path.is_dir() {
+            problems.push(LaunchError::ProfileNotADir(profile.clone()));
+        }
+    }
+    problems
+}
+
+/// Asks the driver's `/status` endpoint whether it can start a session.
+async fn check_driver(url: &str) -> Result<(), LaunchError> {
+    let driver_error = |reason: String| LaunchError::Driver { url: url.to_string(), reason };
+    let response = reqwest::Client::new()
+        .get(format!("{}/status", url.trim_end_matches('/')))
+        .timeout(DRIVER_TIMEOUT)
+        .send()
+        .await
+        .map_err(|err| driver_error(err.without_url().to_string()))?;
+    let status: Value = response.json().await.map_err(|_| driver_error("its /status isn't JSON".to_string()))?;
+    if status["value"]["ready"].as_bool() == Some(false) {
+        return Err(LaunchError::DriverNotReady {
+            url: url.to_string(),
+            message: status["value"]["message"].as_str().unwrap_or("no reason given").to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn find_browser() -> Option<PathBuf> {
+    let on_path = env::var_os("PATH").into_iter().flat_map(|path| env::split_paths(&path).collect::<Vec<_>>()).flat_map(|dir| {
+        BROWSER_NAMES.iter().flat_map(move |name| [dir.join(name), dir.join(format!("{}.exe", name))])
+    });
+    on_path.chain(BROWSER_PATHS.iter().map(PathBuf::from)).find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("moviebot-launch-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Answers one request with `body` as the driver's status.
+    async fn fake_driver(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).await;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+        url
+    }
+
+    #[test]
+    fn args_follow_the_config() {
+        let config = BrowserConfig {
+            headless: true,
+            window_size: Some([1280, 720]),
+            args: vec!["--mute-audio".to_string()],
+            profile_dir: Some("profile".to_string()),
+            ..BrowserConfig::default()
+        };
+        let args = args(&config, &[PathBuf::from("a"), PathBuf::from("b")]);
+        assert_eq!(args[..REQUIRED_ARGS.len()], REQUIRED_ARGS);
+        assert!(args.contains(&"--mute-audio".to_string()));
+        assert!(args.contains(&"--headless=new".to_string()));
+        assert!(args.contains(&"--window-size=1280,720".to_string()));
+        assert!(!args.contains(&"--start-maximized".to_string()));
+        assert!(args.contains(&"--user-data-dir=profile".to_string()));
+        assert_eq!(args.last().unwrap(), "--load-extension=a,b");
+    }
+
+    #[test]
+    fn extension_paths_are_expanded() {
+        let root = temp_dir("extensions");
+        for name in ["adblock", "volume"] {
+            std::fs::create_dir_all(root.join(name)).unwrap();
+            std::fs::write(root.join(name).join("manifest.json"), "{}").unwrap();
+        }
+        std::fs::create_dir_all(root.join("notes")).unwrap();
+
+        let folder = root.to_string_lossy().to_string();
+        let single = root.join("volume").to_string_lossy().to_string();
+        let dirs = extension_dirs(&[folder, single]).unwrap();
+        assert_eq!(dirs, vec![root.join("adblock"), root.join("volume"), root.join("volume")]);
+
+        let empty = root.join("notes").to_string_lossy().to_string();
+        assert!(matches!(extension_dirs(&[empty]), Err(LaunchError::NotAnExtension(_))));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn missing_pieces_are_errors_not_panics() {
+        let config = BrowserConfig {
+            binary: Some("/nowhere/chrome".to_string()),
+            extensions: vec!["/nowhere/Extensions".to_string()],
+            ..BrowserConfig::default()
+        };
+        let err = capabilities(&config).unwrap_err().to_string();
+        assert_eq!(err, "Extensions folder /nowhere/Extensions doesn't exist");
+
+        let config = BrowserConfig { extensions: Vec::new(), ..config };
+        assert!(matches!(capabilities(&config), Err(LaunchError::MissingBinary(_))));
+    }
+
+    #[test]
+    fn the_default_config_launches_without_an_extensions_folder() {
+        assert!(BrowserConfig::default().extensions.is_empty());
+        assert!(capabilities(&BrowserConfig::default()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn preflight_reports_every_problem() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let config = BrowserConfig {
+            driver_url: url.clone(),
+            binary: Some("/nowhere/chrome".to_string()),
+            extensions: vec!["/nowhere/Extensions".to_string()],
+            ..BrowserConfig::default()
+        };
+
+        let problems: Vec<String> = preflight(&config).await.iter().map(ToString::to_string).collect();
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(problems[0].starts_with(&format!("No WebDriver at {}", url)), "{}", problems[0]);
+        assert_eq!(problems[1], "browser.binary /nowhere/chrome doesn't exist");
+    }
+
+    #[tokio::test]
+    async fn driver_status_is_checked() {
+        let url = fake_driver(r#"{"value":{"ready":true,"message":"ChromeDriver ready for new sessions."}}"#).await;
+        check_driver(&url).await.unwrap();
+
+        let url = fake_driver(r#"{"value":{"ready":false,"message":"session limit reached"}}"#).await;
+        let err = check_driver(&url).await.unwrap_err().to_string();
+        assert!(err.ends_with("isn't ready: session limit reached"), "{}", err);
+    }
+}
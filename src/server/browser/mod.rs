@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use thirtyfour::{error::WebDriverResult, By, Cookie, ElementRect};
+
+#[cfg(test)]
+pub mod fake;
+pub mod jwplayer;
+pub mod launch;
+pub mod selectors;
+#[cfg(test)]
+pub mod testpage;
+pub mod wait;
+pub mod webdriver;
+
+pub use webdriver::WebDriverBackend;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Locator {
+    Css(String),
+    XPath(String),
+}
+
+impl Locator {
+    pub fn css(selector: impl Into<String>) -> Self {
+        Locator::Css(selector.into())
+    }
+
+    pub fn xpath(selector: impl Into<String>) -> Self {
+        Locator::XPath(selector.into())
+    }
+
+    pub fn to_by(&self) -> By {
+        match self {
+            Locator::Css(selector) => By::Css(selector.clone()),
+            Locator::XPath(selector) => By::XPath(selector.clone()),
+        }
+    }
+}
+
+/// Everything the playback flows need from a browser. `WebDriverBackend` drives a
+/// real Chrome session; tests use the in-memory `fake::FakeBrowser`.
+#[async_trait]
+pub trait BrowserBackend: Send + Sync {
+    async fn goto(&self, url: &str) -> WebDriverResult<()>;
+    async fn execute(&self, script: &str, args: Vec<Value>) -> WebDriverResult<Value>;
+    async fn current_url(&self) -> WebDriverResult<String>;
+
+    async fn is_present(&self, locator: &Locator) -> WebDriverResult<bool>;
+    /// Whether the element exists, is displayed and is enabled.
+    async fn is_clickable(&self, locator: &Locator) -> WebDriverResult<bool>;
+    async fn click(&self, locator: &Locator) -> WebDriverResult<()>;
+    async fn attr(&self, locator: &Locator, name: &str) -> WebDriverResult<Option<String>>;
+    async fn rect(&self, locator: &Locator) -> WebDriverResult<ElementRect>;
+    /// Clicks `x`/`y` pixels away from the centre of the element.
+    async fn click_at_offset(&self, locator: &Locator, x: i64, y: i64) -> WebDriverResult<()>;
+
+    async fn window_size(&self) -> WebDriverResult<(i64, i64)>;
+    async fn move_mouse(&self, x: i64, y: i64) -> WebDriverResult<()>;
+    async fn click_at(&self, x: i64, y: i64) -> WebDriverResult<()>;
+
+    async fn enter_frame(&self, index: u16) -> WebDriverResult<()>;
+    /// Leaves every frame and returns to the top-level document.
+    async fn enter_default_frame(&self) -> WebDriverResult<()>;
+    async fn windows(&self) -> WebDriverResult<Vec<String>>;
+    async fn switch_to_window(&self, handle: &str) -> WebDriverResult<()>;
+
+    async fn get_all_cookies(&self) -> WebDriverResult<Vec<Cookie>>;
+    async fn add_cookie(&self, cookie: Cookie) -> WebDriverResult<()>;
+    async fn delete_all_cookies(&self) -> WebDriverResult<()>;
+
+    async fn screenshot(&self) -> WebDriverResult<Vec<u8>>;
+    async fn page_source(&self) -> WebDriverResult<String>;
+    /// Browser console messages since the last call, one line each.
+    async fn console_logs(&self) -> WebDriverResult<Vec<String>>;
+    async fn quit(&self) -> WebDriverResult<()>;
+}
+
+/// Gives the mouse a one pixel nudge around the middle of the window, which is
+/// what makes the provider players show their controls.
+pub async fn wake_controls(driver: &dyn BrowserBackend) -> WebDriverResult<(i64, i64)> {
+    let (width, height) = driver.window_size().await?;
+    let center_x = width / 2;
+    let center_y = height / 2;
+    driver.move_mouse(center_x - 1, center_y - 1).await?;
+    driver.move_mouse(center_x, center_y).await?;
+    Ok((center_x, center_y))
+}
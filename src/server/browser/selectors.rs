@@ -0,0 +1,268 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, RwLock},
+    time::Duration,
+};
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use thirtyfour::error::{WebDriverError, WebDriverResult};
+
+use super::{
+    wait::{until, Condition},
+    BrowserBackend, Locator,
+};
+use crate::server::config::CONFIG;
+
+const BUILT_IN: &str = include_str!("selectors.toml");
+
+/// The built-in selectors with the `[selectors]` overrides from the config applied.
+pub static SELECTORS: Lazy<SelectorRegistry> = Lazy::new(|| {
+    let registry = SelectorRegistry::parse(BUILT_IN).expect("built-in selectors.toml");
+    for (name, selectors) in &CONFIG.selectors {
+        if let Err(err) = registry.set_override(name, selectors.clone()) {
+            eprintln!("Ignoring selector override: {}", err);
+        }
+    }
+    registry
+});
+
+#[derive(Deserialize)]
+struct RegistryFile {
+    version: u32,
+    #[serde(flatten)]
+    groups: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+/// How the last lookup of an element went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// `index` is the position of `selector` in the fallbacks, 0 being the preferred one.
+    Matched { index: usize, selector: String },
+    /// None of the fallbacks matched; `error` is the last lookup that failed outright.
+    Failed { error: Option<String> },
+}
+
+/// DOM selectors by logical element (`netflix.skip_forward`), each an ordered list of
+/// fallbacks. Overrides replace an element's whole list and can be set while running.
+pub struct SelectorRegistry {
+    version: u32,
+    built_in: BTreeMap<String, Vec<String>>,
+    overrides: RwLock<BTreeMap<String, Vec<String>>>,
+    outcomes: Mutex<BTreeMap<String, Outcome>>,
+}
+
+impl SelectorRegistry {
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        let file: RegistryFile = toml::from_str(contents)?;
+        let built_in = file
+            .groups
+            .into_iter()
+            .flat_map(|(group, elements)| {
+                elements.into_iter().map(move |(element, selectors)| (format!("{}.{}", group, element), selectors))
+            })
+            .collect();
+        Ok(SelectorRegistry {
+            version: file.version,
+            built_in,
+            overrides: RwLock::new(BTreeMap::new()),
+            outcomes: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.built_in.keys().cloned().collect()
+    }
+
+    pub fn is_overridden(&self, name: &str) -> bool {
+        self.overrides.read().unwrap().contains_key(name)
+    }
+
+    /// Whether the fallbacks of `name` have `{var}` placeholders to fill in before a lookup.
+    pub fn needs_vars(&self, name: &str) -> bool {
+        self.selectors(name).iter().any(|selector| selector.contains('{'))
+    }
+
+    /// The fallbacks for `name`, overridden or built in.
+    pub fn selectors(&self, name: &str) -> Vec<String> {
+        if let Some(selectors) = self.overrides.read().unwrap().get(name) {
+            return selectors.clone();
+        }
+        self.built_in.get(name).cloned().unwrap_or_default()
+    }
+
+    /// Replaces the fallbacks of a known element; an empty list goes back to the built-in ones.
+    pub fn set_override(&self, name: &str, selectors: Vec<String>) -> Result<(), String> {
+        if !self.built_in.contains_key(name) {
+            return Err(format!("unknown element '{}', expected one of {}", name, self.names().join(", ")));
+        }
+        let mut overrides = self.overrides.write().unwrap();
+        if selectors.is_empty() {
+            overrides.remove(name);
+        } else {
+            overrides.insert(name.to_string(), selectors);
+        }
+        Ok(())
+    }
+
+    /// The fallbacks of `name` as locators, with `{key}` placeholders replaced from `vars`.
+    pub fn candidates(&self, name: &str, vars: &[(&str, &str)]) -> Vec<Locator> {
+        self.selectors(name)
+            .iter()
+            .map(|selector| {
+                let selector = vars
+                    .iter()
+                    .fold(selector.clone(), |selector, (key, value)| selector.replace(&format!("{{{}}}", key), value));
+                match selector.strip_prefix("xpath:") {
+                    Some(xpath) => Locator::xpath(xpath),
+                    None => Locator::css(selector),
+                }
+            })
+            .collect()
+    }
+
+    pub fn outcome(&self, name: &str) -> Option<Outcome> {
+        self.outcomes.lock().unwrap().get(name).cloned()
+    }
+
+    /// The first fallback of `name` that is on the page, remembering which one it was.
+    pub async fn find(&self, driver: &dyn BrowserBackend, name: &str, vars: &[(&str, &str)]) -> WebDriverResult<Locator> {
+        self.first_matching(driver, name, vars, Condition::Present).await?.ok_or_else(|| {
+            WebDriverError::NotFound(
+                format!("selector {}", name),
+                format!("none of {:?} is on the page", self.candidates(name, vars)),
+            )
+        })
+    }
+
+    /// Polls until one of the fallbacks of `name` meets `condition`, such as `Condition::Clickable`.
+    pub async fn wait(
+        &self,
+        driver: &dyn BrowserBackend,
+        name: &str,
+        vars: &[(&str, &str)],
+        condition: fn(Locator) -> Condition,
+        timeout: Duration,
+    ) -> WebDriverResult<Locator> {
+        let what = format!("{} to match one of {:?}", name, self.candidates(name, vars));
+        until(&what, timeout, || async {
+            Ok(self.first_matching(driver, name, vars, condition).await?.is_some())
+        })
+        .await?;
+        self.first_matching(driver, name, vars, condition)
+            .await?
+            .ok_or_else(|| WebDriverError::NotFound(format!("selector {}", name), "it went away while waiting".to_string()))
+    }
+
+    async fn first_matching(
+        &self,
+        driver: &dyn BrowserBackend,
+        name: &str,
+        vars: &[(&str, &str)],
+        condition: fn(Locator) -> Condition,
+    ) -> WebDriverResult<Option<Locator>> {
+        let mut error = None;
+        for (index, locator) in self.candidates(name, vars).into_iter().enumerate() {
+            match condition(locator.clone()).holds(driver).await {
+                Ok(true) => {
+                    let selector = self.selectors(name).swap_remove(index);
+                    self.outcomes.lock().unwrap().insert(name.to_string(), Outcome::Matched { index, selector });
+                    return Ok(Some(locator));
+                }
+                Ok(false) => {}
+                Err(err) => error = Some(err.to_string()),
+            }
+        }
+        self.outcomes.lock().unwrap().insert(name.to_string(), Outcome::Failed { error });
+        Ok(None)
+    }
+
+    pub async fn click(&self, driver: &dyn BrowserBackend, name: &str, vars: &[(&str, &str)]) -> WebDriverResult<()> {
+        let locator = self.find(driver, name, vars).await?;
+        driver.click(&locator).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::browser::fake::{BrowserCall, FakeBrowser};
+
+    fn registry() -> SelectorRegistry {
+        SelectorRegistry::parse(BUILT_IN).unwrap()
+    }
+
+    #[test]
+    fn built_in_registry_has_every_element() {
+        let registry = registry();
+        assert!(registry.version() >= 1);
+        for name in ["discord.channel", "discord.go_live", "netflix.skip_forward", "netflix.skip_back", "jwplayer.fullscreen"] {
+            assert!(!registry.selectors(name).is_empty(), "{} has no selectors", name);
+        }
+        assert_eq!(
+            registry.candidates("discord.channel", &[("id", "42")]),
+            vec![Locator::css("[data-list-item-id='channels___42']")]
+        );
+        assert!(matches!(registry.candidates("discord.go_live", &[]).last(), Some(Locator::XPath(_))));
+    }
+
+    #[tokio::test]
+    async fn falls_back_and_records_the_match() {
+        let registry = registry();
+        let browser = FakeBrowser::new();
+        let fallbacks = registry.candidates("netflix.skip_forward", &[]);
+        browser.remove(fallbacks[0].clone());
+
+        registry.click(&browser, "netflix.skip_forward", &[]).await.unwrap();
+        assert_eq!(browser.calls(), vec![BrowserCall::Click(fallbacks[1].clone())]);
+        assert!(matches!(registry.outcome("netflix.skip_forward"), Some(Outcome::Matched { index: 1, .. })));
+
+        browser.remove(fallbacks[1].clone());
+        assert!(registry.find(&browser, "netflix.skip_forward", &[]).await.is_err());
+        assert_eq!(registry.outcome("netflix.skip_forward"), Some(Outcome::Failed { error: None }));
+    }
+
+    #[tokio::test]
+    async fn lookup_errors_move_on_to_the_next_fallback() {
+        let registry = registry();
+        let browser = FakeBrowser::new();
+        let fallbacks = registry.candidates("netflix.skip_forward", &[]);
+        browser.break_lookup(fallbacks[0].clone());
+
+        assert_eq!(registry.find(&browser, "netflix.skip_forward", &[]).await.unwrap(), fallbacks[1]);
+        assert!(matches!(registry.outcome("netflix.skip_forward"), Some(Outcome::Matched { index: 1, .. })));
+
+        browser.remove(fallbacks[1].clone());
+        for locator in fallbacks.iter().skip(2) {
+            browser.remove(locator.clone());
+        }
+        assert!(registry.find(&browser, "netflix.skip_forward", &[]).await.is_err());
+        assert!(matches!(
+            registry.outcome("netflix.skip_forward"),
+            Some(Outcome::Failed { error: Some(error) }) if error.contains("invalid selector")
+        ));
+    }
+
+    #[test]
+    fn templated_elements_need_vars() {
+        let registry = registry();
+        assert!(registry.needs_vars("discord.channel"));
+        assert!(!registry.needs_vars("netflix.skip_forward"));
+    }
+
+    #[test]
+    fn overrides_replace_and_reset() {
+        let registry = registry();
+        registry.set_override("netflix.skip_back", vec!["xpath://button[@data-uia='back']".to_string()]).unwrap();
+        assert_eq!(registry.candidates("netflix.skip_back", &[]), vec![Locator::xpath("//button[@data-uia='back']")]);
+        assert!(registry.is_overridden("netflix.skip_back"));
+
+        registry.set_override("netflix.skip_back", Vec::new()).unwrap();
+        assert_eq!(registry.selectors("netflix.skip_back")[0], "button[data-uia='control-back10']");
+        assert!(registry.set_override("netflix.nope", vec!["a".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+//! Self-hosted copies of the provider player pages, so the playback controls can
+//! run end to end against headless Chrome without a Netflix account or Fasel.
+
+use std::sync::Arc;
+
+use serde::Deserialize;
+use thirtyfour::{error::WebDriverResult, ChromeCapabilities, ChromiumLikeCapabilities, WebDriver};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    task::JoinHandle,
+};
+
+use super::{BrowserBackend, WebDriverBackend};
+
+const PAGES: [(&str, &str); 3] = [
+    ("/netflix.html", include_str!("netflix.html")),
+    ("/jwplayer.html", include_str!("jwplayer.html")),
+    ("/fasel.html", include_str!("fasel.html")),
+];
+
+pub struct TestPageServer {
+    base_url: String,
+    handle: JoinHandle<()>,
+}
+
+impl TestPageServer {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buffer = [0u8; 4096];
+                    let read = stream.read(&mut buffer).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buffer[..read]);
+                    let path = request.split_whitespace().nth(1).unwrap_or("/");
+                    let response = match PAGES.iter().find(|(page, _)| *page == path) {
+                        Some((_, body)) => format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            body.len(),
+                            body
+                        ),
+                        None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+                    };
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+        TestPageServer { base_url, handle }
+    }
+
+    pub fn url(&self, page: &str) -> String {
+        format!("{}/{}", self.base_url, page)
+    }
+}
+
+impl Drop for TestPageServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Connects to chromedriver at `CHROMEDRIVER_URL` (default `http://localhost:9515`).
+/// The browser tests are `#[ignore]`d, so reaching this without a driver is a failure.
+pub async fn headless_chrome() -> Arc<dyn BrowserBackend> {
+    let url = std::env::var("CHROMEDRIVER_URL").unwrap_or_else(|_| "http://localhost:9515".to_string());
+    let mut options = ChromeCapabilities::new();
+    options.set_headless().unwrap();
+    options.add_arg("--window-size=1280,800").unwrap();
+    match WebDriver::new(&url, options).await {
+        Ok(driver) => Arc::new(WebDriverBackend::new(driver)),
+        Err(err) => panic!("No chromedriver at {} for the browser tests: {}", url, err),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+struct PlaybackProgress {
+    position: f64,
+    duration: f64,
+    paused: bool,
+}
+
+const PROGRESS_SCRIPT: &str = r#"
+    const video = document.querySelector('video');
+    return video ? { position: video.currentTime, duration: video.duration, paused: video.paused } : null;
+"#;
+
+/// Reads the state of the first `<video>` on the page, if there is one with a known duration.
+async fn read_progress(driver: &dyn BrowserBackend) -> WebDriverResult<Option<PlaybackProgress>> {
+    let value = driver.execute(PROGRESS_SCRIPT, Vec::new()).await?;
+    Ok(serde_json::from_value(value).ok())
+}
+
+mod tests {
+    use super::*;
+    use crate::server::{
+        browser::{selectors::SELECTORS, Locator},
+        classes::{fasel::Fasel, netflix::Netflix},
+    };
+    use scraper::{Html, Selector};
+
+    fn select_one(page: &str, selector: &str) -> Option<String> {
+        let html = PAGES.iter().find(|(path, _)| *path == page).unwrap().1;
+        let document = Html::parse_document(html);
+        let selector = Selector::parse(selector).unwrap();
+        let element = document.select(&selector).next()?;
+        Some(element.value().attr("data-uia").or(element.value().attr("class")).unwrap_or("").to_string())
+    }
+
+    /// Every CSS fallback of `name`, so a stale one can't hide behind a working one.
+    fn fallbacks(name: &str) -> Vec<String> {
+        SELECTORS
+            .candidates(name, &[])
+            .into_iter()
+            .filter_map(|locator| match locator {
+                Locator::Css(selector) => Some(selector),
+                Locator::XPath(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pages_match_the_registry_selectors() {
+        for selector in fallbacks("netflix.skip_forward") {
+            assert_eq!(select_one("/netflix.html", &selector).as_deref(), Some("control-forward10"), "{}", selector);
+        }
+        for selector in fallbacks("netflix.skip_back") {
+            assert_eq!(select_one("/netflix.html", &selector).as_deref(), Some("control-back10"), "{}", selector);
+        }
+        assert!(select_one("/netflix.html", "button[data-uia='timeline-knob'][aria-valuemax]").is_some());
+        for selector in fallbacks("jwplayer.fullscreen") {
+            assert_eq!(select_one("/jwplayer.html", &selector).as_deref(), Some("jw-icon jw-icon-fullscreen jw-reset"), "{}", selector);
+        }
+    }
+
+    #[tokio::test]
+    async fn server_serves_the_bundled_pages() {
+        let server = TestPageServer::start().await;
+        let body = reqwest::get(server.url("netflix.html")).await.unwrap().text().await.unwrap();
+        assert!(body.contains("timeline-knob"));
+        let missing = reqwest::get(server.url("nope.html")).await.unwrap();
+        assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs chromedriver; run with --ignored and CHROMEDRIVER_URL"]
+    async fn netflix_controls_drive_the_fake_player() {
+        let browser = headless_chrome().await;
+        let server = TestPageServer::start().await;
+        browser.goto(&server.url("netflix.html")).await.unwrap();
+        let netflix = Netflix::new(browser.clone());
+
+        let progress = read_progress(browser.as_ref()).await.unwrap().unwrap();
+        assert!(!progress.paused);
+        assert_eq!(progress.duration, 7200.0);
+
+        netflix.pause().await.unwrap();
+        assert!(read_progress(browser.as_ref()).await.unwrap().unwrap().paused);
+
+        netflix.skip_to_specific_timeline("0.30").await.unwrap();
+        let position = read_progress(browser.as_ref()).await.unwrap().unwrap().position;
+        assert!((position - 1800.0).abs() < 30.0, "seeked to {}", position);
+
+        netflix.skipfront().await.unwrap();
+        let forward = read_progress(browser.as_ref()).await.unwrap().unwrap().position;
+        assert_eq!(forward, position + 10.0);
+
+        netflix.skipback().await.unwrap();
+        netflix.skipback().await.unwrap();
+        let back = read_progress(browser.as_ref()).await.unwrap().unwrap().position;
+        assert_eq!(back, position - 10.0);
+
+        browser.quit().await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "needs chromedriver; run with --ignored and CHROMEDRIVER_URL"]
+    async fn fasel_start_plays_the_framed_jw_player() {
+        let browser = headless_chrome().await;
+        let server = TestPageServer::start().await;
+        Fasel::new(browser.clone()).start(&server.url("fasel.html")).await.unwrap();
+
+        let state = browser.execute("return jwplayer().getState();", Vec::new()).await.unwrap();
+        assert_eq!(state, "playing");
+        let fullscreen = browser.execute("return jwplayer().getFullscreen();", Vec::new()).await.unwrap();
+        assert_eq!(fullscreen, true);
+        assert!(browser.is_present(&Locator::css("#player.jw-state-playing")).await.unwrap());
+
+        browser.quit().await.unwrap();
+    }
+}
@@ -0,0 +1,125 @@
+use std::{fmt, future::Future, time::Duration};
+
+use thirtyfour::error::{WebDriverError, WebDriverResult};
+
+use super::{BrowserBackend, Locator};
+
+/// How often conditions are re-checked.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+const VIDEO_READY_SCRIPT: &str =
+    "const video = document.querySelector('video'); return !!video && video.readyState >= 3;";
+
+/// Something the playback flows wait for instead of sleeping.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Present(Locator),
+    Clickable(Locator),
+    /// The current url contains this text.
+    UrlContains(String),
+    /// At least this many windows are open.
+    Windows(usize),
+    /// The first `<video>` in the current frame has enough data to play (readyState ≥ 3).
+    VideoReady,
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Present(locator) => write!(f, "{:?} to be present", locator),
+            Condition::Clickable(locator) => write!(f, "{:?} to be clickable", locator),
+            Condition::UrlContains(text) => write!(f, "the url to contain '{}'", text),
+            Condition::Windows(count) => write!(f, "{} windows to be open", count),
+            Condition::VideoReady => write!(f, "the video to be ready"),
+        }
+    }
+}
+
+impl Condition {
+    pub async fn holds(&self, driver: &dyn BrowserBackend) -> WebDriverResult<bool> {
+        match self {
+            Condition::Present(locator) => driver.is_present(locator).await,
+            Condition::Clickable(locator) => driver.is_clickable(locator).await,
+            Condition::UrlContains(text) => Ok(driver.current_url().await?.contains(text.as_str())),
+            Condition::Windows(count) => Ok(driver.windows().await?.len() >= *count),
+            Condition::VideoReady => Ok(driver.execute(VIDEO_READY_SCRIPT, Vec::new()).await?.as_bool().unwrap_or(false)),
+        }
+    }
+}
+
+/// Polls `condition` until it holds, failing with a `Timeout` that names `step` after `timeout`.
+pub async fn wait_for(driver: &dyn BrowserBackend, step: &str, condition: Condition, timeout: Duration) -> WebDriverResult<()> {
+    until(&format!("{}: {}", step, condition), timeout, || condition.holds(driver)).await
+}
+
+/// Polls `check` until it returns true. Errors from `check` are retried too, as pages
+/// that are still loading fail scripts and lookups; the last one is kept for the timeout error.
+pub async fn until<F, Fut>(what: &str, timeout: Duration, mut check: F) -> WebDriverResult<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = WebDriverResult<bool>>,
+{
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let last_error = match check().await {
+            Ok(true) => return Ok(()),
+            Ok(false) => None,
+            Err(err) => Some(err),
+        };
+        if tokio::time::Instant::now() >= deadline {
+            let cause = last_error.map(|err| format!(" (last error: {})", err)).unwrap_or_default();
+            return Err(WebDriverError::Timeout(format!(
+                "gave up after {:.1}s waiting for {}{}",
+                timeout.as_secs_f64(),
+                what,
+                cause
+            )));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::browser::fake::FakeBrowser;
+    use serde_json::json;
+
+    #[tokio::test(start_paused = true)]
+    async fn times_out_with_the_step_and_condition() {
+        let browser = FakeBrowser::new();
+        let button = Locator::css("#go-live");
+        browser.remove(button.clone());
+
+        let started = tokio::time::Instant::now();
+        let err = wait_for(&browser, "Go Live", Condition::Clickable(button), Duration::from_secs(5)).await.unwrap_err();
+        assert!(started.elapsed() >= Duration::from_secs(5));
+        let message = err.to_string();
+        assert!(message.contains("Go Live: Css(\"#go-live\") to be clickable"), "{}", message);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn returns_as_soon_as_the_condition_holds() {
+        let browser = FakeBrowser::new();
+        browser.set_windows(&["discord", "blank", "player"]);
+        browser.script_result("readyState >= 3", json!(true));
+
+        let started = tokio::time::Instant::now();
+        wait_for(&browser, "stream window", Condition::Windows(3), Duration::from_secs(5)).await.unwrap();
+        wait_for(&browser, "playback", Condition::VideoReady, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(started.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_until_the_check_passes() {
+        let mut attempts = 0;
+        until("three attempts", Duration::from_secs(5), || {
+            attempts += 1;
+            let done = attempts == 3;
+            async move { Ok(done) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(attempts, 3);
+    }
+}
@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use reqwest::Method;
+use serde_json::{json, Value};
+use thirtyfour::{
+    common::command::FormatRequestData, error::WebDriverResult, Cookie, ElementRect, RequestData, SessionId, WebDriver,
+};
+
+use super::{BrowserBackend, Locator};
+
+pub struct WebDriverBackend {
+    driver: WebDriver,
+}
+
+impl WebDriverBackend {
+    pub fn new(driver: WebDriver) -> Self {
+        WebDriverBackend { driver }
+    }
+}
+
+/// chromedriver's console log endpoint, which only has entries when the session was
+/// created with the `goog:loggingPrefs` capability.
+#[derive(Debug)]
+struct BrowserLog;
+
+impl FormatRequestData for BrowserLog {
+    fn format_request(&self, session_id: &SessionId) -> RequestData {
+        RequestData::new(Method::POST, format!("/session/{}/se/log", session_id)).add_body(json!({ "type": "browser" }))
+    }
+}
+
+#[async_trait]
+impl BrowserBackend for WebDriverBackend {
+    async fn goto(&self, url: &str) -> WebDriverResult<()> {
+        self.driver.goto(url).await
+    }
+
+    async fn execute(&self, script: &str, args: Vec<Value>) -> WebDriverResult<Value> {
+        Ok(self.driver.execute(script, args).await?.json().clone())
+    }
+
+    async fn current_url(&self) -> WebDriverResult<String> {
+        Ok(self.driver.current_url().await?.to_string())
+    }
+
+    async fn is_present(&self, locator: &Locator) -> WebDriverResult<bool> {
+        Ok(!self.driver.find_all(locator.to_by()).await?.is_empty())
+    }
+
+    async fn is_clickable(&self, locator: &Locator) -> WebDriverResult<bool> {
+        match self.driver.find_all(locator.to_by()).await?.first() {
+            Some(element) => element.is_clickable().await,
+            None => Ok(false),
+        }
+    }
+
+    async fn click(&self, locator: &Locator) -> WebDriverResult<()> {
+        self.driver.find(locator.to_by()).await?.click().await
+    }
+
+    async fn attr(&self, locator: &Locator, name: &str) -> WebDriverResult<Option<String>> {
+        self.driver.find(locator.to_by()).await?.attr(name).await
+    }
+
+    async fn rect(&self, locator: &Locator) -> WebDriverResult<ElementRect> {
+        self.driver.find(locator.to_by()).await?.rect().await
+    }
+
+    async fn click_at_offset(&self, locator: &Locator, x: i64, y: i64) -> WebDriverResult<()> {
+        let element = self.driver.find(locator.to_by()).await?;
+        self.driver
+            .action_chain()
+            .move_to_element_center(&element)
+            .move_by_offset(x, y)
+            .click()
+            .perform()
+            .await
+    }
+
+    async fn window_size(&self) -> WebDriverResult<(i64, i64)> {
+        let rect = self.driver.get_window_rect().await?;
+        Ok((rect.width, rect.height))
+    }
+
+    async fn move_mouse(&self, x: i64, y: i64) -> WebDriverResult<()> {
+        self.driver.action_chain().move_to(x, y).perform().await
+    }
+
+    async fn click_at(&self, x: i64, y: i64) -> WebDriverResult<()> {
+        self.driver.action_chain().move_to(x, y).click().perform().await
+    }
+
+    async fn enter_frame(&self, index: u16) -> WebDriverResult<()> {
+        self.driver.enter_frame(index).await
+    }
+
+    async fn enter_default_frame(&self) -> WebDriverResult<()> {
+        self.driver.enter_default_frame().await
+    }
+
+    async fn windows(&self) -> WebDriverResult<Vec<String>> {
+        Ok(self.driver.windows().await?.iter().map(|handle| handle.to_string()).collect())
+    }
+
+    async fn switch_to_window(&self, handle: &str) -> WebDriverResult<()> {
+        self.driver.switch_to_window(handle.to_string().into()).await
+    }
+
+    async fn get_all_cookies(&self) -> WebDriverResult<Vec<Cookie>> {
+        self.driver.get_all_cookies().await
+    }
+
+    async fn add_cookie(&self, cookie: Cookie) -> WebDriverResult<()> {
+        self.driver.add_cookie(cookie).await
+    }
+
+    async fn delete_all_cookies(&self) -> WebDriverResult<()> {
+        self.driver.delete_all_cookies().await
+    }
+
+    async fn screenshot(&self) -> WebDriverResult<Vec<u8>> {
+        self.driver.screenshot_as_png().await
+    }
+
+    async fn page_source(&self) -> WebDriverResult<String> {
+        self.driver.source().await
+    }
+
+    async fn console_logs(&self) -> WebDriverResult<Vec<String>> {
+        let entries: Vec<Value> = self.driver.cmd(BrowserLog).await?.value()?;
+        Ok(entries
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{} {} {}",
+                    entry["timestamp"].as_i64().unwrap_or_default(),
+                    entry["level"].as_str().unwrap_or("INFO"),
+                    entry["message"].as_str().unwrap_or_default()
+                )
+            })
+            .collect())
+    }
+
+    async fn quit(&self) -> WebDriverResult<()> {
+        self.driver.clone().quit().await
+    }
+}
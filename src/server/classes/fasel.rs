@@ -1,87 +1,62 @@
-use std::sync::Arc;
-use serde_json::json;
-use thirtyfour::{error::WebDriverResult, By, WebDriver};
-use reqwest::Client;
-use scraper::{Html, Selector};
-use regex::Regex;
-use crate::server::functions::DRIVER_INSTANCE;
+use std::{sync::Arc, time::Duration};
+use thirtyfour::error::WebDriverResult;
+use crate::server::browser::{jwplayer::JwPlayer, wait::{wait_for, Condition}, BrowserBackend};
 
-use super::netflix::ShowResult;
-pub struct Fasel {
-    driver: Arc<WebDriver>,
-}
-pub struct FaselSearcher;
+const PLAYER_TIMEOUT: Duration = Duration::from_secs(15);
+const PLAYBACK_TIMEOUT: Duration = Duration::from_secs(30);
 
-impl FaselSearcher {
-    pub async fn _search(movie_name: &str) -> Result<Vec<(String, String, String, ShowResult)>, Box<dyn std::error::Error + Send + Sync>> {
-        let client = Client::new();
-        let url = format!("https://web184.faselhd.cafe/?s={}", movie_name);
-        let res = client.get(&url).send().await?.text().await?;
-        
-        let document = Html::parse_document(&res);
-        let movie_selector = Selector::parse("div.postDiv")
-        .map_err(|e| format!("Selector parse error: {:?}", e))?;
-    
-    let img_selector = Selector::parse("div.imgdiv-class img")
-        .map_err(|e| format!("Selector parse error: {:?}", e))?;
-    
-    let title_selector = Selector::parse("div.h1")
-        .map_err(|e| format!("Selector parse error: {:?}", e))?;
-    
-        let movies = document.select(&movie_selector).take(9);
-        let images = document.select(&img_selector).take(9);
-        
-        let mut results = Vec::new();
-        let arabic_regex = Regex::new(r"[\u0600-\u06FF]+")?;
-    
-        let a_selector = Selector::parse("a")
-        .map_err(|e| format!("Selector parse error: {:?}", e))?;
-    
-        for (movie, img) in movies.zip(images) {
-            if let Some(a_tag) = movie.select(&a_selector).next() {
-                if let Some(href) = a_tag.value().attr("href") {
-                    let name = movie
-                        .select(&title_selector)
-                        .next()
-                        .map(|title| title.text().collect::<String>().trim().to_string())
-                        .unwrap_or_else(|| "Unknown".to_string());
-        
-                    let cleaned_name = arabic_regex.replace_all(&name, "").to_string();
-                    let img_src = img.value().attr("data-src").unwrap_or("").to_string();
-        
-                    results.push((href.to_string(), img_src, cleaned_name, ShowResult { show_data: json!({ "movie_id": "0"}), is_show: false}));
-                }
-            }
-        }
-        
-        Ok(results)
-    }
+pub struct Fasel {
+    driver: Arc<dyn BrowserBackend>,
 }
 impl Fasel {
-    pub async fn new(driver: WebDriver) -> Self {
+    pub fn new(driver: Arc<dyn BrowserBackend>) -> Self {
         Fasel {
-            driver: Arc::new(driver),
+            driver,
         }
     }
+    /// Opens the page and plays its embedded JW Player fullscreen. Works for any site
+    /// that embeds JW Player, which is what the scraped providers link to.
     pub async fn start(&self, url: &str) -> WebDriverResult<()>{
         self.driver.goto(url).await?;
-        self.driver.set_implicit_wait_timeout(std::time::Duration::from_secs(10)).await?;
+        // The player iframe is lazy loaded below the post header.
         self.driver.execute("window.scrollTo(0, 1100);", Vec::new()).await?;
-        self.driver.enter_frame(0).await?;
-        self.driver.find(By::XPath("/html/body/div[1]/div[2]/div[13]/div[1]/div/div/div[2]/div")).await?.click().await?;
-        self.driver.execute("document.querySelector(\"#player > div.jw-wrapper.jw-reset > div.jw-controls.jw-reset > div.jw-controlbar.jw-reset > div.jw-reset.jw-button-container > div:nth-child(18)\").click()", Vec::new()).await?;
-        Ok(())
-    }
-    pub async fn pause() -> WebDriverResult<()> {
-        let driver_lock = DRIVER_INSTANCE.lock().await;
-    
-        if let Some(driver) = driver_lock.as_ref() {
-            driver.goto("https://google.com").await?;
-        } else {
-            println!("Nice")
-        }
-    
+        let player = JwPlayer::new(self.driver.clone());
+        player.wait(PLAYER_TIMEOUT).await?;
+        player.play().await?;
+        wait_for(self.driver.as_ref(), "Fasel playback", Condition::VideoReady, PLAYBACK_TIMEOUT).await?;
+        player.fullscreen().await?;
         Ok(())
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::browser::{fake::{BrowserCall, FakeBrowser}, Locator};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn start_plays_the_player_inside_the_first_frame() {
+        let browser = Arc::new(FakeBrowser::new());
+        browser.script_result_in_frame(&[], "window.frames.length", json!({ "player": false, "frames": 1 }));
+        browser.script_result_in_frame(&[0], "window.frames.length", json!({ "player": true, "frames": 0 }));
+        browser.script_result_in_frame(&[0], "getCaptionsList", json!({
+            "state": "playing", "position": 0, "duration": 5400, "volume": 100, "quality": 0, "captions": 0,
+        }));
+        browser.script_result_in_frame(&[0], "readyState >= 3", json!(true));
+        Fasel::new(browser.clone()).start("https://fasel.example/movie").await.unwrap();
+
+        let calls = browser.calls();
+        assert_eq!(calls[0], BrowserCall::Goto("https://fasel.example/movie".to_string()));
+        let frame = calls.iter().position(|call| *call == BrowserCall::EnterFrame(0)).unwrap();
+        let play = calls.iter().position(|call| matches!(call, BrowserCall::Execute(script) if script.starts_with("jwplayer().play();"))).unwrap();
+        assert!(frame < play);
+        assert_eq!(calls.last(), Some(&BrowserCall::Click(Locator::css(".jw-icon-fullscreen"))));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn start_fails_when_there_is_no_player() {
+        let browser = Arc::new(FakeBrowser::new());
+        assert!(Fasel::new(browser).start("https://fasel.example/movie").await.is_err());
+    }
+}
@@ -1,3 +1,146 @@
+use async_trait::async_trait;
+
+use netflix::{NetflixSearcher, ShowResult};
+use shahid::ShahidSearcher;
+
 pub mod fasel;
 pub mod netflix;
-pub mod shahid;
\ No newline at end of file
+pub mod scraped;
+pub mod shahid;
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// `(url, image url, title, kind)` for one search hit.
+pub type SearchResult = (String, String, String, ShowResult);
+
+/// What `/info` and the select menus show about a title. Providers fill in what they know.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TitleDetails {
+    pub title: String,
+    pub synopsis: Option<String>,
+    pub year: Option<u32>,
+    pub maturity: Option<String>,
+    pub runtime_minutes: Option<u32>,
+    pub seasons: Option<u32>,
+    pub episodes: Option<u32>,
+    pub genres: Vec<String>,
+    pub cast: Vec<String>,
+}
+
+impl TitleDetails {
+    /// A one-line summary such as `2017 · 16+ · 3 Seasons · Thriller`.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(year) = self.year {
+            parts.push(year.to_string());
+        }
+        if let Some(maturity) = &self.maturity {
+            parts.push(maturity.clone());
+        }
+        match (self.seasons, self.runtime_minutes) {
+            (Some(1), _) => parts.push("1 Season".to_string()),
+            (Some(seasons), _) => parts.push(format!("{} Seasons", seasons)),
+            (None, Some(minutes)) if minutes >= 60 => parts.push(format!("{}h {}m", minutes / 60, minutes % 60)),
+            (None, Some(minutes)) => parts.push(format!("{}m", minutes)),
+            (None, None) => {}
+        }
+        if let Some(episodes) = self.episodes {
+            parts.push(format!("{} Episodes", episodes));
+        }
+        if let Some(genre) = self.genres.first() {
+            parts.push(genre.clone());
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Curated lists a provider can show instead of search results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrowseList {
+    Top10,
+    Trending,
+    Genre(u32),
+    MyList,
+    ContinueWatching,
+}
+
+impl BrowseList {
+    pub const NAMES: [&'static str; 5] = ["Top 10", "Trending", "My List", "Continue Watching", "Genre"];
+
+    /// Parses one of `NAMES`; `Genre` also needs the genre id.
+    pub fn parse(name: &str, genre: Option<u32>) -> Option<Self> {
+        match name.to_lowercase().replace(' ', "").as_str() {
+            "top10" => Some(BrowseList::Top10),
+            "trending" => Some(BrowseList::Trending),
+            "mylist" => Some(BrowseList::MyList),
+            "continuewatching" => Some(BrowseList::ContinueWatching),
+            "genre" => genre.map(BrowseList::Genre),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            BrowseList::Top10 => "Top 10".to_string(),
+            BrowseList::Trending => "Trending".to_string(),
+            BrowseList::Genre(id) => format!("Genre {}", id),
+            BrowseList::MyList => "My List".to_string(),
+            BrowseList::ContinueWatching => "Continue Watching".to_string(),
+        }
+    }
+}
+
+/// A streaming service the bot can search.
+#[async_trait]
+pub trait Provider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchResult>, Error>;
+
+    /// `(url, image url)` of the top hit for `query`, for `/info`. Providers whose search
+    /// runs follow-up requests per result override this to skip them.
+    async fn best_match(&self, query: &str) -> Result<Option<(String, String)>, Error> {
+        Ok(self.search(query).await?.into_iter().next().map(|(url, image, _, _)| (url, image)))
+    }
+
+    /// Details for the title behind a search result's url.
+    async fn details(&self, url: &str) -> Result<TitleDetails, Error> {
+        Err(format!("{} doesn't provide title details for {}", self.name(), url).into())
+    }
+
+    async fn browse(&self, list: &BrowseList) -> Result<Vec<SearchResult>, Error> {
+        Err(format!("{} doesn't have a {} list", self.name(), list.name()).into())
+    }
+
+    /// A show's seasons, shaped like Netflix's episode selector data
+    /// (`{videoId, seasons: {edges: [{node: {title, videoId}}]}}`) so one menu reads them all.
+    async fn show(&self, show_id: &str) -> Result<ShowResult, Error> {
+        Err(format!("{} doesn't list seasons for {}", self.name(), show_id).into())
+    }
+
+    /// `(number, title, id)` for every episode of a season.
+    async fn episodes(&self, season_id: &str) -> Result<Vec<(i64, String, i64)>, Error> {
+        Err(format!("{} doesn't list episodes for {}", self.name(), season_id).into())
+    }
+
+    /// Where to stream an episode returned by `episodes`.
+    async fn episode_url(&self, season_id: &str, episode_id: &str) -> Result<String, Error> {
+        Err(format!("{} can't play episode {} of {}", self.name(), episode_id, season_id).into())
+    }
+}
+
+/// Looks a provider up by the service name users type, case-insensitively.
+pub fn provider(service: &str) -> Option<&'static dyn Provider> {
+    match service.to_lowercase().as_str() {
+        "netflix" => Some(&NetflixSearcher),
+        "shahid" => Some(&ShahidSearcher),
+        _ => scraped::find(service).map(|provider| provider as &dyn Provider),
+    }
+}
+
+/// Every service name `provider` knows, for autocompletion.
+pub fn provider_names() -> Vec<&'static str> {
+    let mut names = vec![NetflixSearcher.name(), ShahidSearcher.name()];
+    names.extend(scraped::SCRAPERS.iter().map(|provider| provider.name()));
+    names
+}
@@ -0,0 +1,131 @@
+//! Browse lists (the home page rows, My List and genre pages) through pathEvaluator.
+
+use serde_json::{json, Value};
+
+use crate::server::classes::{BrowseList, SearchResult};
+
+use super::{falcor::{JsonGraph, Path}, ShowResult};
+
+/// Titles fetched per list; the collage only shows the first nine with artwork.
+const LIST_LENGTH: i64 = 24;
+/// Home page rows scanned when looking for a row by context.
+const ROW_COUNT: i64 = 40;
+
+/// The lolomo ("list of lists of movies") row context for lists that live on the home page.
+pub fn row_context(list: &BrowseList) -> Option<&'static str> {
+    match list {
+        BrowseList::Top10 => Some("topTen"),
+        BrowseList::Trending => Some("trendingNow"),
+        BrowseList::MyList => Some("queue"),
+        BrowseList::ContinueWatching => Some("continueWatching"),
+        BrowseList::Genre(_) => None,
+    }
+}
+
+pub fn row_paths() -> Vec<Path> {
+    vec![Path::new().key("lolomo").range(0, ROW_COUNT - 1).keys(&["context", "displayName"])]
+}
+
+pub fn find_row(graph: &JsonGraph, context: &str) -> Option<i64> {
+    graph
+        .list(&["lolomo"])
+        .into_iter()
+        .map(|(index, _)| index)
+        .find(|index| graph.get(&["lolomo", &index.to_string(), "context"]).and_then(Value::as_str) == Some(context))
+}
+
+/// Where a list's titles hang off the graph: a home page row, or a genre's title list.
+pub fn list_root(list: &BrowseList, row: Option<i64>) -> Vec<String> {
+    match (list, row) {
+        (BrowseList::Genre(id), _) => vec!["genres".to_string(), id.to_string(), "su".to_string()],
+        (_, Some(row)) => vec!["lolomo".to_string(), row.to_string()],
+        (_, None) => Vec::new(),
+    }
+}
+
+pub fn title_paths(root: &[String]) -> Vec<Path> {
+    let list = || root.iter().fold(Path::new(), |path, key| path.key(key.as_str())).range(0, LIST_LENGTH - 1);
+    vec![
+        list().keys(&["title", "summary"]),
+        list().key("boxarts").key("_342x192").key("webp"),
+    ]
+}
+
+pub fn titles_from_graph(graph: &JsonGraph, root: &[String]) -> Vec<SearchResult> {
+    let field = |index: i64, keys: &[&str]| {
+        let mut path: Vec<String> = root.to_vec();
+        path.push(index.to_string());
+        path.extend(keys.iter().map(|key| key.to_string()));
+        graph.get(&path).cloned()
+    };
+
+    graph
+        .list(root)
+        .into_iter()
+        .filter_map(|(index, _)| {
+            let summary = field(index, &["summary"])?;
+            let id = summary.get("id")?.to_string();
+            let title = field(index, &["title"])?.as_str()?.to_string();
+            let image = field(index, &["boxarts", "_342x192", "webp"])
+                .and_then(|boxart| boxart.get("url").and_then(Value::as_str).map(str::to_string))
+                .filter(|url| !url.is_empty())?;
+            let is_show = summary.get("type").and_then(Value::as_str) == Some("show");
+            Some((
+                format!("https://netflix.com/watch/{}", id),
+                image,
+                title,
+                ShowResult { show_data: json!({ "movie_id": id }), is_show },
+            ))
+        })
+        .take(9)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_row_and_reads_its_titles() {
+        let graph = JsonGraph::from_response(json!({ "jsonGraph": {
+            "lolomo": { "$type": "ref", "value": ["lolomos", "abc"] },
+            "lolomos": { "abc": {
+                "0": { "$type": "ref", "value": ["lists", "continue"] },
+                "1": { "$type": "ref", "value": ["lists", "top"] }
+            } },
+            "lists": {
+                "continue": { "context": { "$type": "atom", "value": "continueWatching" } },
+                "top": {
+                    "context": { "$type": "atom", "value": "topTen" },
+                    "0": { "$type": "ref", "value": ["videos", "80100172"] },
+                    "1": { "$type": "ref", "value": ["videos", "81040344"] }
+                }
+            },
+            "videos": {
+                "80100172": {
+                    "title": { "$type": "atom", "value": "Dark" },
+                    "summary": { "$type": "atom", "value": { "id": 80100172, "type": "show" } },
+                    "boxarts": { "_342x192": { "webp": { "$type": "atom", "value": { "url": "https://img/dark.webp" } } } }
+                },
+                "81040344": {
+                    "title": { "$type": "atom", "value": "No Artwork" },
+                    "summary": { "$type": "atom", "value": { "id": 81040344, "type": "movie" } }
+                }
+            }
+        } }));
+
+        let row = find_row(&graph, row_context(&BrowseList::Top10).unwrap());
+        assert_eq!(row, Some(1));
+        let root = list_root(&BrowseList::Top10, row);
+        let titles = titles_from_graph(&graph, &root);
+        assert_eq!(titles.len(), 1);
+        assert_eq!(titles[0].0, "https://netflix.com/watch/80100172");
+        assert_eq!(titles[0].1, "https://img/dark.webp");
+        assert!(titles[0].3.is_show);
+
+        assert_eq!(
+            title_paths(&list_root(&BrowseList::Genre(83), None))[1].to_json(),
+            r#"["genres","83","su",{"from":0,"to":23},"boxarts","_342x192","webp"]"#
+        );
+    }
+}
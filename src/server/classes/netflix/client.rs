@@ -0,0 +1,319 @@
+use std::time::SystemTime;
+
+use once_cell::sync::Lazy;
+use rand::Rng;
+use reqwest::{Method, RequestBuilder};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::sync::watch;
+
+use crate::server::{
+    config::CONFIG,
+    cookies::{CookieJar, StoredCookie},
+    http::{HttpResponse, ProviderHttp, HTTP},
+};
+
+use super::{
+    falcor::{JsonGraph, Path},
+    graphql::{parse_response, Extensions, GraphqlRequest, NetflixError, QueryRegistry},
+    COOK,
+};
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+const GRAPHQL_URL: &str = "https://web.prod.cloud.netflix.com/graphql";
+const PATH_EVALUATOR_URL: &str = "https://www.netflix.com/nq/website/memberapi/release/pathEvaluator?webp=true&drmSystem=widevine&isVolatileBillboardsEnabled=true&isTop10Supported=true&isTop10KidsSupported=true&hasVideoMerchInBob=true&hasVideoMerchInJaw=true&falcor_server=0.1.0&withSize=true&materialize=true&original_path=%2Fshakti%2Fmre%2FpathEvaluator";
+
+const COOKIE_DOMAIN: &str = ".netflix.com";
+
+pub static NETFLIX: Lazy<NetflixClient> = Lazy::new(|| {
+    NetflixClient::new(&HTTP, CONFIG.netflix.profile.clone(), CONFIG.netflix.queries.clone(), load_cookies())
+});
+
+/// `NETFLIX_COOKIES` (a header value) wins over the configured cookie file, which
+/// wins over the configured header, which wins over `COOK`.
+fn load_cookies() -> CookieJar {
+    if let Ok(header) = std::env::var("NETFLIX_COOKIES") {
+        return CookieJar::parse_header(&header, COOKIE_DOMAIN);
+    }
+    if let Some(path) = &CONFIG.netflix.cookies_file {
+        match CookieJar::load(path) {
+            Ok(jar) => return jar.for_domain(COOKIE_DOMAIN.trim_start_matches('.')),
+            Err(err) => eprintln!("{}, falling back to the built-in cookies", err),
+        }
+    }
+    CookieJar::parse_header(CONFIG.netflix.cookies.as_deref().unwrap_or(COOK), COOKIE_DOMAIN)
+}
+
+/// The browser and device the requests claim to come from. Defaults to the Opera GX
+/// session the headers were originally captured from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetflixProfile {
+    pub accept_language: String,
+    pub sec_ch_ua: String,
+    pub platform: String,
+    pub platform_version: String,
+    pub browser_name: String,
+    pub browser_version: String,
+    pub client_type: String,
+    pub esn: String,
+    pub esn_prefix: String,
+    pub os_full_name: String,
+    pub os_name: String,
+    pub os_version: String,
+    pub ui_version: String,
+    pub user_guid: String,
+    pub auth_url: String,
+}
+
+impl Default for NetflixProfile {
+    fn default() -> Self {
+        NetflixProfile {
+            accept_language: "en-US,en;q=0.9".to_string(),
+            sec_ch_ua: "\"Chromium\";v=\"128\", \"Not;A=Brand\";v=\"24\", \"Opera GX\";v=\"114\"".to_string(),
+            platform: "\"Windows\"".to_string(),
+            platform_version: "\"15.0.0\"".to_string(),
+            browser_name: "Opera".to_string(),
+            browser_version: "114".to_string(),
+            client_type: "akira".to_string(),
+            esn: "NFCDOP-01-LKGUFQQ17TKAG2MJMU7PNKTCLEFD02".to_string(),
+            esn_prefix: "NFCDOP-01-".to_string(),
+            os_full_name: "Windows 10".to_string(),
+            os_name: "Windows".to_string(),
+            os_version: "10.0".to_string(),
+            ui_version: "v43acacdd".to_string(),
+            user_guid: "JYH2XQN2E5BRZHXFX2IFOXEV5Y".to_string(),
+            auth_url: "1739263844285.2XpQna0eN71gFJg+gzS5ycqyaAo=".to_string(),
+        }
+    }
+}
+
+/// Whether the cookies still log in, as of the last Netflix response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionState {
+    Unknown,
+    Active,
+    NeedsReauth { reason: String, since: SystemTime },
+}
+
+/// One pooled HTTP client for every Netflix endpoint, sending the same header
+/// profile and cookies the web player would.
+pub struct NetflixClient {
+    http: &'static ProviderHttp,
+    profile: NetflixProfile,
+    queries: QueryRegistry,
+    cookies: CookieJar,
+    session: watch::Sender<SessionState>,
+}
+
+impl NetflixClient {
+    pub fn new(http: &'static ProviderHttp, profile: NetflixProfile, queries: QueryRegistry, cookies: CookieJar) -> Self {
+        NetflixClient {
+            http,
+            profile,
+            queries,
+            cookies,
+            session: watch::Sender::new(SessionState::Unknown),
+        }
+    }
+
+    /// The jar the HTTP requests use, for seeding the browser session.
+    pub fn cookies(&self) -> Vec<StoredCookie> {
+        self.cookies.cookies().to_vec()
+    }
+
+    pub fn session(&self) -> SessionState {
+        self.session.borrow().clone()
+    }
+
+    /// Notified whenever the session state changes.
+    pub fn subscribe(&self) -> watch::Receiver<SessionState> {
+        self.session.subscribe()
+    }
+
+    fn set_session(&self, state: SessionState) {
+        self.session.send_if_modified(|current| {
+            let changed = match (&*current, &state) {
+                (SessionState::NeedsReauth { .. }, SessionState::NeedsReauth { .. }) => false,
+                (current, state) => current != state,
+            };
+            if changed {
+                *current = state;
+            }
+            changed
+        });
+    }
+
+    /// Flags logged-out answers: 401/403, or the login page served instead of JSON.
+    /// A JSON answer doesn't prove the login works, as Netflix serves logged-out
+    /// sessions empty graphs; see `confirm_login`.
+    pub(super) fn check_session(&self, response: &HttpResponse) -> Result<(), NetflixError> {
+        let redirected_to_login = response
+            .headers
+            .iter()
+            .any(|(name, value)| name.eq_ignore_ascii_case("location") && value.contains("/login"));
+        let reason = if matches!(response.status.as_u16(), 401 | 403) {
+            Some(format!("HTTP {}", response.status.as_u16()))
+        } else if redirected_to_login || response.body.trim_ascii_start().starts_with(b"<") {
+            Some("redirected to the login page".to_string())
+        } else {
+            None
+        };
+        match reason {
+            Some(reason) => Err(self.expire(reason)),
+            None => Ok(()),
+        }
+    }
+
+    /// Marks the session as working, after a response only a logged-in session gets.
+    pub(crate) fn confirm_login(&self) {
+        self.set_session(SessionState::Active);
+    }
+
+    /// Marks the session as logged out, for responses that only look wrong once parsed.
+    pub(crate) fn expire(&self, reason: String) -> NetflixError {
+        eprintln!("Netflix session needs re-auth: {}", reason);
+        self.set_session(SessionState::NeedsReauth { reason: reason.clone(), since: SystemTime::now() });
+        NetflixError::SessionExpired(reason)
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<HttpResponse, Error> {
+        let response = self.http.send("netflix", request).await?;
+        self.check_session(&response)?;
+        Ok(response)
+    }
+
+    fn request(&self, method: Method, url: &str, content_type: &str) -> RequestBuilder {
+        let profile = &self.profile;
+        self.http
+            .client()
+            .request(method, url)
+            .header("accept", "*/*")
+            .header("accept-language", &profile.accept_language)
+            .header("cache-control", "no-cache")
+            .header("content-type", content_type)
+            .header("pragma", "no-cache")
+            .header("priority", "u=1, i")
+            .header("sec-ch-ua", &profile.sec_ch_ua)
+            .header("sec-ch-ua-mobile", "?0")
+            .header("sec-ch-ua-model", "\"\"")
+            .header("sec-ch-ua-platform", &profile.platform)
+            .header("sec-ch-ua-platform-version", &profile.platform_version)
+            .header("sec-fetch-dest", "empty")
+            .header("sec-fetch-mode", "cors")
+            .header("sec-fetch-site", "same-origin")
+            .header("x-netflix.browsername", &profile.browser_name)
+            .header("x-netflix.browserversion", &profile.browser_version)
+            .header("x-netflix.client.request.name", "ui/falcorUnclassified")
+            .header("x-netflix.clienttype", &profile.client_type)
+            .header("x-netflix.esn", &profile.esn)
+            .header("x-netflix.esnprefix", &profile.esn_prefix)
+            .header("x-netflix.nq.stack", "prod")
+            .header("x-netflix.osfullname", &profile.os_full_name)
+            .header("x-netflix.osname", &profile.os_name)
+            .header("x-netflix.osversion", &profile.os_version)
+            .header("x-netflix.request.attempt", "1")
+            .header("x-netflix.request.client.context", "{\"appstate\":\"foreground\"}")
+            .header("x-netflix.request.client.user.guid", &profile.user_guid)
+            .header("x-netflix.request.id", request_id())
+            .header("x-netflix.uiversion", &profile.ui_version)
+            .header("Cookie", self.cookies.header())
+    }
+
+    /// Posts a GraphQL request body and deserializes the response.
+    pub async fn graphql<B: Serialize + ?Sized, T: DeserializeOwned>(&self, body: &B) -> Result<T, Error> {
+        let request = self
+            .request(Method::POST, GRAPHQL_URL, "application/json")
+            .body(serde_json::to_string(body)?);
+        Ok(self.send(request).await?.json()?)
+    }
+
+    /// Runs the persisted query registered for `operation` and checks the `data`
+    /// it returns against `D`.
+    pub async fn query<V: Serialize, D: DeserializeOwned>(&self, operation: &str, variables: V) -> Result<D, Error> {
+        let request = GraphqlRequest {
+            operation_name: operation,
+            variables,
+            extensions: Extensions { persisted_query: self.queries.get(operation)? },
+        };
+        match parse_response(operation, self.graphql(&request).await?) {
+            Err(NetflixError::Graphql { messages, .. }) if messages.iter().any(|message| is_auth_error(message)) => {
+                Err(self.expire(messages.join("; ")).into())
+            }
+            result => Ok(result?),
+        }
+    }
+
+    /// Asks the Falcor pathEvaluator for `paths` and returns the resulting graph.
+    pub async fn path_evaluator(&self, paths: &[Path]) -> Result<JsonGraph, Error> {
+        let paths: Vec<String> = paths.iter().map(Path::to_json).collect();
+        let mut form_data: Vec<(&str, &str)> = paths.iter().map(|path| ("path", path.as_str())).collect();
+        form_data.push(("authURL", &self.profile.auth_url));
+        let request = self
+            .request(Method::POST, PATH_EVALUATOR_URL, "application/x-www-form-urlencoded")
+            .body(serde_urlencoded::to_string(&form_data)?);
+        Ok(JsonGraph::from_response(self.send(request).await?.json()?))
+    }
+}
+
+fn is_auth_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    ["unauthenticated", "unauthorized", "not logged in", "login required"]
+        .iter()
+        .any(|needle| message.contains(needle))
+}
+
+/// A fresh 32 character hex id, like the web player sends with every request.
+fn request_id() -> String {
+    format!("{:032x}", rand::thread_rng().gen::<u128>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::http::{FixtureStore, HttpMode};
+
+    static TEST_HTTP: Lazy<ProviderHttp> = Lazy::new(|| ProviderHttp::new(HttpMode::Live, FixtureStore::new("fixtures")));
+
+    #[test]
+    fn logged_out_responses_flag_the_session() {
+        let client = NetflixClient::new(&TEST_HTTP, NetflixProfile::default(), QueryRegistry::default(), CookieJar::default());
+        let mut updates = client.subscribe();
+        let response = |status: u16, body: &str| HttpResponse {
+            status: reqwest::StatusCode::from_u16(status).unwrap(),
+            headers: Vec::new(),
+            body: body.as_bytes().to_vec(),
+        };
+
+        client.check_session(&response(200, r#"{"jsonGraph":{}}"#)).unwrap();
+        assert_eq!(client.session(), SessionState::Unknown, "an empty graph isn't proof of a login");
+        client.confirm_login();
+        assert_eq!(client.session(), SessionState::Active);
+        assert!(updates.has_changed().unwrap());
+        updates.mark_unchanged();
+
+        let err = client.check_session(&response(200, "\n<!DOCTYPE html><title>Netflix</title>")).unwrap_err();
+        assert!(matches!(err, NetflixError::SessionExpired(_)));
+        assert!(matches!(client.session(), SessionState::NeedsReauth { .. }));
+        assert!(updates.has_changed().unwrap());
+        updates.mark_unchanged();
+
+        client.check_session(&response(401, "")).unwrap_err();
+        assert!(!updates.has_changed().unwrap(), "repeat failures shouldn't re-alert");
+        assert!(is_auth_error("User is UNAUTHENTICATED"));
+    }
+
+    #[test]
+    fn requests_carry_the_profile_and_a_fresh_id() {
+        let profile = NetflixProfile { browser_name: "Chrome".to_string(), ..NetflixProfile::default() };
+        let client = NetflixClient::new(&TEST_HTTP, profile, QueryRegistry::default(), CookieJar::parse_header("NetflixId=abc", COOKIE_DOMAIN));
+
+        let first = client.request(Method::POST, GRAPHQL_URL, "application/json").build().unwrap();
+        let second = client.request(Method::POST, GRAPHQL_URL, "application/json").build().unwrap();
+        assert_eq!(first.headers()["x-netflix.browsername"], "Chrome");
+        assert_eq!(first.headers()["cookie"], "NetflixId=abc");
+        let first_id = first.headers()["x-netflix.request.id"].to_str().unwrap();
+        assert_eq!(first_id.len(), 32);
+        assert_ne!(first_id, second.headers()["x-netflix.request.id"].to_str().unwrap());
+    }
+}
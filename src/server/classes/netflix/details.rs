@@ -0,0 +1,122 @@
+//! Title details read from the pathEvaluator's `videos` branch.
+
+use serde_json::Value;
+
+use crate::server::classes::TitleDetails;
+
+use super::falcor::{JsonGraph, Path};
+
+/// How many genres and cast members are asked for.
+const LIST_LENGTH: i64 = 4;
+
+pub fn details_paths(video_id: &str) -> Vec<Path> {
+    let video = || Path::new().key("videos").key(video_id);
+    vec![
+        video().keys(&["title", "synopsis", "releaseYear", "maturity", "runtime", "seasonCount"]),
+        video().key("genres").range(0, LIST_LENGTH).keys(&["id", "name"]),
+        video().key("cast").range(0, LIST_LENGTH).keys(&["id", "name"]),
+    ]
+}
+
+/// `None` when the graph has nothing for the video, e.g. for an id Netflix doesn't know.
+pub fn details_from_graph(graph: &JsonGraph, video_id: &str) -> Option<TitleDetails> {
+    let field = |name: &str| graph.get(&["videos", video_id, name]);
+    let title = field("title")?.as_str()?.to_string();
+
+    let maturity = field("maturity").and_then(|maturity| match maturity {
+        Value::String(rating) => Some(rating.clone()),
+        other => other.pointer("/rating/value").and_then(Value::as_str).map(str::to_string),
+    });
+    let names = |list: &str| -> Vec<String> {
+        graph
+            .list(&["videos", video_id, list])
+            .into_iter()
+            .filter_map(|(index, _)| graph.get(&["videos", video_id, list, &index.to_string(), "name"]))
+            .filter_map(|name| name.as_str().map(str::to_string))
+            .collect()
+    };
+
+    Some(TitleDetails {
+        title,
+        synopsis: field("synopsis").and_then(Value::as_str).map(str::to_string),
+        year: field("releaseYear").and_then(Value::as_u64).map(|year| year as u32),
+        maturity,
+        runtime_minutes: field("runtime").and_then(Value::as_u64).map(|seconds| (seconds / 60) as u32),
+        seasons: field("seasonCount").and_then(Value::as_u64).map(|count| count as u32),
+        episodes: None,
+        genres: names("genres"),
+        cast: names("cast"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{
+        classes::netflix::{
+            client::{NetflixClient, NetflixProfile},
+            graphql::QueryRegistry,
+        },
+        cookies::CookieJar,
+        http::{FixtureStore, HttpMode, ProviderHttp},
+    };
+    use once_cell::sync::Lazy;
+    use serde_json::json;
+
+    /// Replays the redacted responses checked in under `fixtures/`.
+    static REPLAY_HTTP: Lazy<ProviderHttp> = Lazy::new(|| {
+        ProviderHttp::new(HttpMode::Replay, FixtureStore::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures")))
+    });
+
+    #[test]
+    fn reads_details_through_refs() {
+        let graph = JsonGraph::from_response(json!({ "jsonGraph": {
+            "videos": { "80100172": {
+                "title": { "$type": "atom", "value": "Dark" },
+                "synopsis": { "$type": "atom", "value": "A missing child sets four families on a frantic hunt." },
+                "releaseYear": { "$type": "atom", "value": 2017 },
+                "maturity": { "$type": "atom", "value": { "rating": { "value": "16+" } } },
+                "seasonCount": { "$type": "atom", "value": 3 },
+                "genres": {
+                    "0": { "$type": "ref", "value": ["genres", "89811"] },
+                    "1": { "$type": "ref", "value": ["genres", "1372"] }
+                },
+                "cast": { "0": { "$type": "ref", "value": ["person", "30011564"] } }
+            } },
+            "genres": {
+                "89811": { "name": { "$type": "atom", "value": "German TV Shows" } },
+                "1372": { "name": { "$type": "atom", "value": "Mysteries" } }
+            },
+            "person": { "30011564": { "name": { "$type": "atom", "value": "Louis Hofmann" } } }
+        } }));
+
+        let details = details_from_graph(&graph, "80100172").unwrap();
+        assert_eq!(details.year, Some(2017));
+        assert_eq!(details.maturity.as_deref(), Some("16+"));
+        assert_eq!(details.genres, vec!["German TV Shows", "Mysteries"]);
+        assert_eq!(details.cast, vec!["Louis Hofmann"]);
+        assert_eq!(details.summary(), "2017 · 16+ · 3 Seasons · German TV Shows");
+        assert!(details_from_graph(&graph, "1").is_none());
+    }
+
+    #[tokio::test]
+    async fn parses_a_recorded_details_response() {
+        let client = NetflixClient::new(&REPLAY_HTTP, NetflixProfile::default(), QueryRegistry::default(), CookieJar::default());
+        let graph = client.path_evaluator(&details_paths("80057281")).await.unwrap();
+
+        let details = details_from_graph(&graph, "80057281").unwrap();
+        assert_eq!(details.title, "Stranger Things");
+        assert_eq!(details.year, Some(2016));
+        assert_eq!(details.seasons, Some(4));
+        assert_eq!(details.genres, vec!["TV Horror", "Teen TV Shows"]);
+        assert_eq!(details.cast, vec!["Winona Ryder", "David Harbour"]);
+    }
+
+    #[test]
+    fn paths_ask_for_every_field() {
+        assert_eq!(
+            details_paths("80100172")[1].to_json(),
+            r#"["videos","80100172","genres",{"from":0,"to":4},["id","name"]]"#
+        );
+    }
+}
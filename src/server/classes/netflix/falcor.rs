@@ -0,0 +1,170 @@
+//! Falcor paths for the pathEvaluator endpoint and a resolver for the jsonGraph it returns.
+
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+
+/// Refs pointing back at themselves (or each other) stop being followed after this many hops.
+const MAX_REF_HOPS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathKey {
+    Key(String),
+    Range { from: i64, to: i64 },
+    Keys(Vec<String>),
+}
+
+impl Serialize for PathKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            PathKey::Key(key) => key.serialize(serializer),
+            PathKey::Range { from, to } => json!({ "from": from, "to": to }).serialize(serializer),
+            PathKey::Keys(keys) => keys.serialize(serializer),
+        }
+    }
+}
+
+/// A path set such as `["search", "query", "dark", {"from":0,"to":1}, "summary"]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Path(Vec<PathKey>);
+
+impl Path {
+    pub fn new() -> Self {
+        Path::default()
+    }
+
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.0.push(PathKey::Key(key.into()));
+        self
+    }
+
+    /// An inclusive range of indexes, as Falcor expects.
+    pub fn range(mut self, from: i64, to: i64) -> Self {
+        self.0.push(PathKey::Range { from, to });
+        self
+    }
+
+    pub fn keys(mut self, keys: &[&str]) -> Self {
+        self.0.push(PathKey::Keys(keys.iter().map(|key| key.to_string()).collect()));
+        self
+    }
+
+    /// The JSON form sent as a `path` form field.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("falcor paths are plain JSON")
+    }
+}
+
+/// The `jsonGraph` of a pathEvaluator response. Lookups follow `$type: ref`
+/// nodes back to the root and unwrap `$type: atom` values.
+#[derive(Debug, Clone, Default)]
+pub struct JsonGraph {
+    root: Value,
+}
+
+impl JsonGraph {
+    pub fn new(root: Value) -> Self {
+        JsonGraph { root }
+    }
+
+    /// Takes the `jsonGraph` out of a full response body; a missing graph is empty.
+    pub fn from_response(mut response: Value) -> Self {
+        JsonGraph::new(response.get_mut("jsonGraph").map(Value::take).unwrap_or(Value::Null))
+    }
+
+    pub fn get<K: AsRef<str>>(&self, path: &[K]) -> Option<&Value> {
+        let path: Vec<String> = path.iter().map(|key| key.as_ref().to_string()).collect();
+        self.resolve(&path, 0).map(unwrap_atom)
+    }
+
+    /// The integer-keyed children of a list node, in index order, with refs followed.
+    pub fn list<K: AsRef<str>>(&self, path: &[K]) -> Vec<(i64, &Value)> {
+        let path: Vec<String> = path.iter().map(|key| key.as_ref().to_string()).collect();
+        let Some(object) = self.resolve(&path, 0).and_then(Value::as_object) else {
+            return Vec::new();
+        };
+        let mut items: Vec<(i64, &Value)> = object
+            .keys()
+            .filter_map(|key| {
+                let index = key.parse().ok()?;
+                let mut item_path = path.clone();
+                item_path.push(key.clone());
+                Some((index, self.resolve(&item_path, 0)?))
+            })
+            .collect();
+        items.sort_by_key(|(index, _)| *index);
+        items
+    }
+
+    fn resolve(&self, path: &[String], hops: usize) -> Option<&Value> {
+        let mut node = &self.root;
+        for (depth, key) in path.iter().enumerate() {
+            node = node.get(key)?;
+            if let Some(target) = ref_target(node) {
+                if hops >= MAX_REF_HOPS {
+                    return None;
+                }
+                let mut redirected = target;
+                redirected.extend_from_slice(&path[depth + 1..]);
+                return self.resolve(&redirected, hops + 1);
+            }
+        }
+        Some(node)
+    }
+}
+
+fn ref_target(node: &Value) -> Option<Vec<String>> {
+    if node.get("$type").and_then(Value::as_str) != Some("ref") {
+        return None;
+    }
+    let keys = node.get("value")?.as_array()?;
+    Some(
+        keys.iter()
+            .map(|key| match key {
+                Value::String(key) => key.clone(),
+                other => other.to_string(),
+            })
+            .collect(),
+    )
+}
+
+fn unwrap_atom(node: &Value) -> &Value {
+    match node.get("$type").and_then(Value::as_str) {
+        Some("atom") => node.get("value").unwrap_or(&Value::Null),
+        _ => node,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_escape_and_serialize_ranges() {
+        let path = Path::new()
+            .key("search")
+            .key("say \"hello\"")
+            .range(0, 47)
+            .keys(&["summary", "queue"]);
+        assert_eq!(path.to_json(), r#"["search","say \"hello\"",{"from":0,"to":47},["summary","queue"]]"#);
+    }
+
+    #[test]
+    fn resolver_follows_refs_and_unwraps_atoms() {
+        let graph = JsonGraph::from_response(json!({ "jsonGraph": {
+            "videos": { "80100172": { "title": { "$type": "atom", "value": "Dark" } } },
+            "lists": { "row": {
+                "1": { "$type": "ref", "value": ["videos", 80100172] },
+                "0": { "$type": "ref", "value": ["videos", "missing"] },
+                "10": { "$type": "ref", "value": ["lists", "loop"] }
+            }, "loop": { "$type": "ref", "value": ["lists", "loop"] } }
+        } }));
+
+        assert_eq!(graph.get(&["lists", "row", "1", "title"]), Some(&json!("Dark")));
+        assert_eq!(graph.get(&["lists", "row", "0", "title"]), None);
+        assert_eq!(graph.get(&["lists", "loop", "title"]), None);
+        let items = graph.list(&["lists", "row"]);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].0, 1);
+    }
+}
@@ -0,0 +1,253 @@
+//! Typed requests and responses for the persisted GraphQL queries the web player uses.
+
+use std::{collections::HashMap, fmt};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+pub const EPISODE_SELECTOR: &str = "PreviewModalEpisodeSelector";
+pub const SEASON_EPISODES: &str = "PreviewModalEpisodeSelectorSeasonEpisodes";
+
+/// Ids the queries were captured with, used unless `[netflix.queries]` overrides them.
+const DEFAULT_QUERIES: [(&str, &str, u32); 2] = [
+    (EPISODE_SELECTOR, "7515a9d0-9422-4bbd-b7fe-aefa7f17ef5a", 102),
+    (SEASON_EPISODES, "380568aa-ec71-479c-832e-2e1f0ec13ec2", 102),
+];
+
+#[derive(Debug)]
+pub enum NetflixError {
+    /// No persisted query id is known for the operation.
+    UnknownQuery(String),
+    /// The response didn't have the shape the operation is expected to return.
+    Schema { operation: String, source: serde_json::Error },
+    /// Netflix answered with GraphQL errors instead of data.
+    Graphql { operation: String, messages: Vec<String> },
+    /// The cookies no longer log in; someone has to export fresh ones.
+    SessionExpired(String),
+}
+
+impl fmt::Display for NetflixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetflixError::UnknownQuery(operation) => write!(f, "no persisted query registered for {}", operation),
+            NetflixError::Schema { operation, source } => {
+                write!(f, "{} response didn't match the expected schema: {}", operation, source)
+            }
+            NetflixError::Graphql { operation, messages } => write!(f, "{} failed: {}", operation, messages.join("; ")),
+            NetflixError::SessionExpired(reason) => {
+                write!(f, "The Netflix session has expired ({}), an admin needs to refresh the cookies", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetflixError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NetflixError::Schema { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedQuery {
+    pub id: String,
+    pub version: u32,
+}
+
+/// Persisted query ids by operation name. Entries from the config replace the
+/// built-in ones, so a rotated id can be fixed without a rebuild.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(transparent)]
+pub struct QueryRegistry {
+    overrides: HashMap<String, PersistedQuery>,
+}
+
+impl QueryRegistry {
+    pub fn get(&self, operation: &str) -> Result<PersistedQuery, NetflixError> {
+        if let Some(query) = self.overrides.get(operation) {
+            return Ok(query.clone());
+        }
+        DEFAULT_QUERIES
+            .iter()
+            .find(|(name, _, _)| *name == operation)
+            .map(|(_, id, version)| PersistedQuery { id: id.to_string(), version: *version })
+            .ok_or_else(|| NetflixError::UnknownQuery(operation.to_string()))
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphqlRequest<'a, V> {
+    pub operation_name: &'a str,
+    pub variables: V,
+    pub extensions: Extensions,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Extensions {
+    pub persisted_query: PersistedQuery,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphqlResponse {
+    data: Option<Value>,
+    #[serde(default)]
+    errors: Vec<GraphqlErrorMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphqlErrorMessage {
+    message: String,
+}
+
+/// Pulls `data` out of a GraphQL response body and checks it against `D`.
+pub fn parse_response<D: DeserializeOwned>(operation: &str, body: Value) -> Result<D, NetflixError> {
+    let schema = |source| NetflixError::Schema { operation: operation.to_string(), source };
+    let response: GraphqlResponse = serde_json::from_value(body).map_err(schema)?;
+    match response.data {
+        Some(data) if !data.is_null() => serde_json::from_value(data).map_err(schema),
+        _ if !response.errors.is_empty() => Err(NetflixError::Graphql {
+            operation: operation.to_string(),
+            messages: response.errors.into_iter().map(|error| error.message).collect(),
+        }),
+        _ => Err(schema(serde::de::Error::missing_field("data"))),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Connection<T> {
+    pub edges: Vec<Edge<T>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_info: Option<PageInfo>,
+}
+
+impl<T> Connection<T> {
+    /// The cursor to request the next page with, if Netflix says there is one.
+    pub fn next_cursor(&self) -> Option<&str> {
+        self.page_info
+            .as_ref()
+            .filter(|page| page.has_next_page)
+            .and_then(|page| page.end_cursor.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    #[serde(default)]
+    pub end_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Edge<T> {
+    pub node: T,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeSelectorVariables<'a> {
+    pub show_id: &'a str,
+    pub season_count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seasons_after: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EpisodeSelectorData {
+    pub videos: Vec<ShowVideo>,
+}
+
+/// The title as the episode selector sees it; movies come back without `seasons`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShowVideo {
+    #[serde(default)]
+    pub video_id: Option<i64>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub seasons: Option<Connection<Season>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Season {
+    pub title: String,
+    pub video_id: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonEpisodesVariables<'a> {
+    pub season_id: &'a str,
+    pub count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<&'a str>,
+    pub opaque_image_format: &'a str,
+    pub artwork_context: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeasonEpisodesData {
+    pub videos: Vec<SeasonVideo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeasonVideo {
+    pub episodes: Connection<Episode>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Episode {
+    pub title: String,
+    pub number: i64,
+    pub video_id: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn registry_prefers_overrides() {
+        let registry: QueryRegistry = serde_json::from_value(json!({
+            SEASON_EPISODES: { "id": "rotated", "version": 103 }
+        }))
+        .unwrap();
+        assert_eq!(registry.get(SEASON_EPISODES).unwrap(), PersistedQuery { id: "rotated".to_string(), version: 103 });
+        assert_eq!(registry.get(EPISODE_SELECTOR).unwrap().id, "7515a9d0-9422-4bbd-b7fe-aefa7f17ef5a");
+        assert!(matches!(registry.get("Nope"), Err(NetflixError::UnknownQuery(_))));
+    }
+
+    #[test]
+    fn parses_episodes_and_reports_schema_drift() {
+        let body = json!({ "data": { "videos": [{ "episodes": { "edges": [
+            { "node": { "title": "Secrets", "number": 1, "videoId": 80100001 } }
+        ] } }] } });
+        let data: SeasonEpisodesData = parse_response(SEASON_EPISODES, body).unwrap();
+        assert_eq!(data.videos[0].episodes.edges[0].node.video_id, 80100001);
+        assert_eq!(data.videos[0].episodes.next_cursor(), None);
+
+        let paged = json!({ "data": { "videos": [{ "episodes": {
+            "edges": [],
+            "pageInfo": { "hasNextPage": true, "endCursor": "MzA=" }
+        } }] } });
+        let data: SeasonEpisodesData = parse_response(SEASON_EPISODES, paged).unwrap();
+        assert_eq!(data.videos[0].episodes.next_cursor(), Some("MzA="));
+
+        let renamed = json!({ "data": { "titles": [] } });
+        let err = parse_response::<EpisodeSelectorData>(EPISODE_SELECTOR, renamed).unwrap_err();
+        assert!(matches!(err, NetflixError::Schema { .. }), "{}", err);
+
+        let failed = json!({ "data": null, "errors": [{ "message": "PersistedQueryNotFound" }] });
+        let err = parse_response::<EpisodeSelectorData>(EPISODE_SELECTOR, failed).unwrap_err();
+        assert_eq!(err.to_string(), "PreviewModalEpisodeSelector failed: PersistedQueryNotFound");
+    }
+}
@@ -0,0 +1,590 @@
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use serde_json::{json, Value};
+use std::{sync::Arc, time::Duration};
+use thirtyfour::error::{WebDriverError, WebDriverResult};
+
+use crate::server::{
+    browser::{
+        selectors::SELECTORS,
+        wait::{until, wait_for, Condition},
+        wake_controls, BrowserBackend, Locator,
+    },
+    classes::{BrowseList, Provider, SearchResult, TitleDetails},
+    config::CONFIG,
+    cookies::StoredCookie,
+};
+
+pub mod browse;
+pub mod client;
+pub mod details;
+pub mod falcor;
+pub mod graphql;
+use client::{NetflixClient, NETFLIX};
+use falcor::{JsonGraph, Path};
+use graphql::{
+    EpisodeSelectorData, EpisodeSelectorVariables, SeasonEpisodesData, SeasonEpisodesVariables, ShowVideo,
+    EPISODE_SELECTOR, SEASON_EPISODES,
+};
+
+/// Seasons and episodes requested per GraphQL page.
+const PAGE_SIZE: u32 = 30;
+/// Stops following cursors after this many pages, in case one never runs out.
+const MAX_PAGES: usize = 20;
+/// How many is_show lookups a search runs at once.
+const KIND_LOOKUPS: usize = 4;
+/// Deleting cookies is near instant; anything longer means the session is stuck.
+const COOKIE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Covers the watch page loading and the stream buffering once it has.
+const PAGE_TIMEOUT: Duration = Duration::from_secs(30);
+const SEARCH_SESSION: &str = "@@NAPA-49279779-77a7-46f8-a83a-f031d1ef037c";
+//Put full cookies here
+pub(crate) const COOK: &str = "your_cookies_here";
+pub struct Netflix {
+    driver: Arc<dyn BrowserBackend>,
+    profile_name: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ShowResult {
+    pub show_data: Value,
+    pub is_show: bool,
+}
+
+
+
+pub struct NetflixSearcher;
+
+impl NetflixSearcher {
+    pub async fn is_show(
+        movie_id: &str,
+    ) -> Result<ShowResult, Box<dyn std::error::Error + Send + Sync>> {
+        let mut show: Option<ShowVideo> = None;
+        let mut cursor: Option<String> = None;
+        for _ in 0..MAX_PAGES {
+            let data: EpisodeSelectorData = NETFLIX
+                .query(EPISODE_SELECTOR, EpisodeSelectorVariables {
+                    show_id: movie_id,
+                    season_count: PAGE_SIZE,
+                    seasons_after: cursor.as_deref(),
+                })
+                .await?;
+            let Some(video) = data.videos.into_iter().next() else { break };
+            let Some(page) = video.seasons.clone() else {
+                show.get_or_insert(video);
+                break;
+            };
+            cursor = page.next_cursor().map(str::to_string);
+            match show.as_mut().and_then(|show| show.seasons.as_mut()) {
+                Some(seasons) => {
+                    seasons.edges.extend(page.edges);
+                    seasons.page_info = page.page_info;
+                }
+                None => show = Some(video),
+            }
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        match show {
+            Some(video) if video.seasons.is_some() => Ok(ShowResult {
+                show_data: serde_json::to_value(video)?,
+                is_show: true,
+            }),
+            _ => Ok(ShowResult {
+                show_data: json!({ "movie_id": movie_id }),
+                is_show: false,
+            }),
+        }
+    }
+    pub async fn search(
+        movie_name: &str,
+    ) -> Result<Vec<(String, String, String, ShowResult)>, Box<dyn std::error::Error + Send + Sync>>
+    {
+        let found = Self::search_titles(movie_name).await?;
+        let mut results = Vec::new();
+        let ids: Vec<String> = found.iter().map(|(entity_id, _, _)| entity_id.clone()).collect();
+        let kinds = lookup_kinds(&ids, |id| async move { Self::is_show(&id).await }).await;
+        for ((entity_id, img_url, display_string), check_show) in found.into_iter().zip(kinds) {
+            results.push((
+                format!("https://netflix.com/watch/{}", entity_id),
+                img_url,
+                display_string,
+                check_show,
+            ));
+        }
+
+        Ok(results)
+    }
+
+    /// `(entity id, image url, title)` for the top hits, from the one pathEvaluator call.
+    async fn search_titles(movie_name: &str) -> Result<Vec<(String, String, String)>, Box<dyn std::error::Error + Send + Sync>> {
+        let graph = NETFLIX.path_evaluator(&Self::search_paths(movie_name)).await?;
+        let search_id = search_id(&NETFLIX, &graph)?;
+
+        let mut summaries = Vec::new();
+        for row in ["0", "1"] {
+            for (index, _) in graph.list(&["searchPage", &search_id, row]) {
+                if let Some(summary) = graph.get(&["searchPage", &search_id, row, &index.to_string(), "summary"]) {
+                    summaries.push(summary);
+                }
+            }
+        }
+
+        let found: Vec<(String, String, String)> = summaries
+            .into_iter()
+            .filter(|summary| summary.get("imgUrl").and_then(|v| v.as_str()).map(|url| !url.is_empty()).unwrap_or(false))
+            .take(9)
+            .map(|summary| {
+                let entity_id = summary
+                    .get("entityId")
+                    .map(|v| v.to_string())
+                    .unwrap_or("".to_string());
+                let img_url = summary
+                    .get("imgUrl")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                let display_string = summary
+                    .get("displayString")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                (entity_id, img_url, display_string)
+            })
+            .collect();
+        Ok(found)
+    }
+
+    pub async fn details(video_id: &str) -> Result<TitleDetails, Box<dyn std::error::Error + Send + Sync>> {
+        let graph = NETFLIX.path_evaluator(&details::details_paths(video_id)).await?;
+        details::details_from_graph(&graph, video_id).ok_or_else(|| format!("Netflix has no details for {}", video_id).into())
+    }
+
+    pub async fn browse(list: &BrowseList) -> Result<Vec<SearchResult>, Box<dyn std::error::Error + Send + Sync>> {
+        let row = match browse::row_context(list) {
+            Some(context) => {
+                let rows = NETFLIX.path_evaluator(&browse::row_paths()).await?;
+                Some(browse::find_row(&rows, context).ok_or_else(|| format!("Netflix isn't showing a {} row", list.name()))?)
+            }
+            None => None,
+        };
+        let root = browse::list_root(list, row);
+        let graph = NETFLIX.path_evaluator(&browse::title_paths(&root)).await?;
+        Ok(browse::titles_from_graph(&graph, &root))
+    }
+
+    fn search_paths(movie_name: &str) -> Vec<Path> {
+        let query = || Path::new().key("search").key("query").key(SEARCH_SESSION).key(movie_name);
+        vec![
+            query().key("summary"),
+            query().range(0, 1).key("summary"),
+            query().range(0, 1).range(0, 47).key("summary"),
+            query()
+                .range(0, 1)
+                .range(0, 47)
+                .key("reference")
+                .keys(&["availability", "episodeCount", "inRemindMeList", "itemSummary", "queue", "summary"]),
+        ]
+    }
+    pub async fn get_episodes_for_shows(
+        season_id: &str,
+    ) -> Result<Vec<(i64, String, i64)>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut results = Vec::new();
+        let mut cursor: Option<String> = None;
+        for _ in 0..MAX_PAGES {
+            let data: SeasonEpisodesData = NETFLIX
+                .query(SEASON_EPISODES, SeasonEpisodesVariables {
+                    season_id,
+                    count: PAGE_SIZE,
+                    after: cursor.as_deref(),
+                    opaque_image_format: "WEBP",
+                    artwork_context: json!({}),
+                })
+                .await?;
+            let Some(video) = data.videos.into_iter().next() else { break };
+            cursor = video.episodes.next_cursor().map(str::to_string);
+            results.extend(
+                video.episodes.edges
+                    .into_iter()
+                    .map(|edge| (edge.node.number, edge.node.title, edge.node.video_id)),
+            );
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(results)
+    }
+}
+
+#[async_trait]
+impl Provider for NetflixSearcher {
+    fn name(&self) -> &'static str {
+        "Netflix"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchResult>, Box<dyn std::error::Error + Send + Sync>> {
+        NetflixSearcher::search(query).await
+    }
+
+    /// Skips the kind lookups, which `/info` doesn't need.
+    async fn best_match(&self, query: &str) -> Result<Option<(String, String)>, Box<dyn std::error::Error + Send + Sync>> {
+        let found = NetflixSearcher::search_titles(query).await?;
+        Ok(found.into_iter().next().map(|(entity_id, img_url, _)| (format!("https://netflix.com/watch/{}", entity_id), img_url)))
+    }
+
+    async fn details(&self, url: &str) -> Result<TitleDetails, Box<dyn std::error::Error + Send + Sync>> {
+        let video_id = url.trim_end_matches('/').rsplit('/').next().unwrap_or(url);
+        NetflixSearcher::details(video_id).await
+    }
+
+    async fn browse(&self, list: &BrowseList) -> Result<Vec<SearchResult>, Box<dyn std::error::Error + Send + Sync>> {
+        NetflixSearcher::browse(list).await
+    }
+
+    async fn show(&self, show_id: &str) -> Result<ShowResult, Box<dyn std::error::Error + Send + Sync>> {
+        NetflixSearcher::is_show(show_id).await
+    }
+
+    async fn episodes(&self, season_id: &str) -> Result<Vec<(i64, String, i64)>, Box<dyn std::error::Error + Send + Sync>> {
+        NetflixSearcher::get_episodes_for_shows(season_id).await
+    }
+
+    async fn episode_url(&self, _season_id: &str, episode_id: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(format!("https://netflix.com/watch/{}", episode_id))
+    }
+}
+
+/// Runs `lookup` for every id, at most `KIND_LOOKUPS` at a time, keeping the input order.
+/// A failed lookup is logged and treated as a title of unknown kind (played as a movie).
+async fn lookup_kinds<F, Fut>(ids: &[String], lookup: F) -> Vec<ShowResult>
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = Result<ShowResult, Box<dyn std::error::Error + Send + Sync>>>,
+{
+    stream::iter(ids.iter().cloned())
+        .map(|id| {
+            let fallback = id.clone();
+            let lookup = lookup(id);
+            async move {
+                lookup.await.unwrap_or_else(|e| {
+                    eprintln!("Couldn't tell whether {} is a show: {}", fallback, e);
+                    ShowResult {
+                        show_data: json!({ "movie_id": fallback, "kind": "unknown" }),
+                        is_show: false,
+                    }
+                })
+            }
+        })
+        .buffered(KIND_LOOKUPS)
+        .collect()
+        .await
+}
+
+/// The id Netflix files the results under. A logged-out session still gets a graph back,
+/// just without a `searchPage`, so a missing one expires the session and a present one
+/// confirms it.
+fn search_id(client: &NetflixClient, graph: &JsonGraph) -> Result<String, graphql::NetflixError> {
+    let search_id = graph
+        .get(&["searchPage"])
+        .and_then(|page| page.as_object())
+        .and_then(|page| page.keys().next().cloned())
+        .ok_or_else(|| client.expire("the search response has no searchPage".to_string()))?;
+    client.confirm_login();
+    Ok(search_id)
+}
+
+impl Netflix {
+    pub fn new(driver: Arc<dyn BrowserBackend>) -> Self {
+        Netflix {
+            driver,
+            profile_name: CONFIG.netflix.profile_name.clone(),
+        }
+    }
+    pub async fn start(&self, url: &str) -> WebDriverResult<()> {
+        self.driver.goto("https://netflix.com").await?;
+        self.driver.delete_all_cookies().await?;
+        let driver = self.driver.as_ref();
+        until("Netflix cookies to clear", COOKIE_TIMEOUT, || async move {
+            Ok(driver.get_all_cookies().await?.is_empty())
+        })
+        .await?;
+        add_cookies(self.driver.as_ref(), &NETFLIX.cookies()).await?;
+        
+        self.driver.goto(url).await?;
+        println!("Cookies added successfully!");
+        // Netflix either starts the title or stops on "Who's watching?".
+        SELECTORS.wait(driver, "netflix.watch_page", &[], Condition::Present, PAGE_TIMEOUT).await?;
+        self.pass_profile_gate(url).await?;
+        wait_for(driver, "Netflix playback", Condition::VideoReady, PAGE_TIMEOUT).await
+    }
+
+    /// Picks the configured profile if Netflix stops on "Who's watching?", then
+    /// goes back to `url`, which the gate replaced.
+    async fn pass_profile_gate(&self, url: &str) -> WebDriverResult<()> {
+        let names = self.profile_names().await?;
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        let index = choose_profile(&names, self.profile_name.as_deref())?;
+        println!("Selecting Netflix profile {}", names[index]);
+        SELECTORS
+            .click(self.driver.as_ref(), "netflix.profile_link", &[("index", &(index + 1).to_string())])
+            .await?;
+        self.driver.goto(url).await
+    }
+
+    /// The names on the "Who's watching?" gate, from the first profile-name selector that finds any.
+    async fn profile_names(&self) -> WebDriverResult<Vec<String>> {
+        for locator in SELECTORS.candidates("netflix.profile_names", &[]) {
+            let Locator::Css(selector) = locator else { continue };
+            if let Value::Array(names) = self.driver.execute(PROFILE_NAMES_SCRIPT, vec![json!(selector)]).await? {
+                if !names.is_empty() {
+                    return Ok(names.into_iter().filter_map(|name| name.as_str().map(str::to_string)).collect());
+                }
+            }
+        }
+        Ok(Vec::new())
+    }
+    pub async fn pause(&self) -> WebDriverResult<()> {
+        let (center_x, center_y) = wake_controls(self.driver.as_ref()).await?;
+        self.driver.click_at(center_x, center_y).await?;
+
+        println!("Clicked");
+        Ok(())
+    }
+    pub async fn skipfront(&self) -> WebDriverResult<()> {
+        wake_controls(self.driver.as_ref()).await?;
+        SELECTORS.click(self.driver.as_ref(), "netflix.skip_forward", &[]).await
+    }
+    pub async fn skipback(&self) -> WebDriverResult<()> {
+        wake_controls(self.driver.as_ref()).await?;
+        SELECTORS.click(self.driver.as_ref(), "netflix.skip_back", &[]).await
+    }
+    pub async fn skip_to_specific_timeline(&self, time_input: &str) -> WebDriverResult<()> {
+        // Convert input time format to milliseconds
+        let target_time_ms = if let Some(ms) = convert_time_format(time_input) {
+            ms
+        } else {
+            println!("Invalid time format: {}", time_input);
+            return Err(WebDriverError::NotFound("Invalid time format.".into(), "nice".into()));
+        };
+
+        // Small movement to avoid focus issues
+        wake_controls(self.driver.as_ref()).await?;
+
+        // Locate timeline elements
+        let timeline_bar = SELECTORS.find(self.driver.as_ref(), "netflix.timeline_bar", &[]).await?;
+        let slider_knob = SELECTORS.find(self.driver.as_ref(), "netflix.timeline_knob", &[]).await?;
+
+        // Get the maximum duration from the knob
+        let max_time_str = self.driver.attr(&slider_knob, "aria-valuemax").await?;
+        let max_time = max_time_str
+        .unwrap_or("0".to_string())
+        .parse::<u64>()
+        .unwrap_or(0) * 1000; // Convert seconds to milliseconds
+        
+        if max_time == 0 {
+            println!("Error: max time is 0.");
+            return Err(WebDriverError::NotFound("Invalid time format.".into(), "nice".into()));
+        }
+
+        // Ensure target time is within range
+        let target_time_ms = target_time_ms.min(max_time);
+
+        // Calculate percentage position
+        let percentage = target_time_ms as f64 / max_time as f64;
+
+        // Get the timeline bar's location and size
+        let rect = self.driver.rect(&timeline_bar).await?;
+        let target_x = (rect.width * percentage).min(rect.width - 1.0); // Target X inside the bar
+
+        // The click is relative to the centre of the bar
+        let offset_x: i64 = (target_x - rect.width / 2.0).round() as i64;
+        let offset_y: i64 = 0; // No vertical movement needed
+
+        // Move to timeline position and click
+        self.driver.click_at_offset(&timeline_bar, offset_x, offset_y).await?;
+
+        println!("Skipped to {}ms ({:.2}% of the video).", target_time_ms, percentage * 100.0);
+        Ok(())
+    }
+}
+
+const PROFILE_NAMES_SCRIPT: &str =
+    "return Array.from(document.querySelectorAll(arguments[0])).map(e => e.textContent.trim());";
+
+fn choose_profile(names: &[String], wanted: Option<&str>) -> WebDriverResult<usize> {
+    match wanted {
+        Some(wanted) => names
+            .iter()
+            .position(|name| name.trim().eq_ignore_ascii_case(wanted.trim()))
+            .ok_or_else(|| WebDriverError::NotFound(
+                format!("Netflix profile '{}'", wanted),
+                format!("the account only has {}", names.join(", ")),
+            )),
+        None if names.len() == 1 => Ok(0),
+        None => Err(WebDriverError::NotFound(
+            "Netflix profile".to_string(),
+            format!("set netflix.profile_name to one of {}", names.join(", ")),
+        )),
+    }
+}
+
+fn convert_time_format(time_str: &str) -> Option<u64> {
+    let parts: Vec<&str> = time_str.split('.').collect();
+    
+    let hours: u64 = parts.first()?.parse().ok()?; // Extract hours
+    let minutes: u64 = parts.get(1).unwrap_or(&"0").parse().ok()?; // Extract minutes
+
+    if minutes >= 60 { return None; } // Prevent invalid minute values
+
+    let total_minutes = (hours * 60) + minutes;
+    Some(total_minutes * 60 * 1000) // Convert to milliseconds
+}
+
+/// The site the browser is on while the cookies are added.
+const COOKIE_SITE: &str = "netflix.com";
+
+async fn add_cookies(driver: &dyn BrowserBackend, cookies: &[StoredCookie]) -> WebDriverResult<()> {
+    for cookie in cookies {
+        driver.add_cookie(cookie.to_webdriver(COOKIE_SITE)).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::browser::fake::{BrowserCall, FakeBrowser};
+    use crate::server::cookies::CookieJar;
+    use crate::server::http::{FixtureStore, HttpMode, HttpResponse, ProviderHttp};
+    use client::{NetflixProfile, SessionState};
+    use graphql::{NetflixError, QueryRegistry};
+    use once_cell::sync::Lazy;
+    use thirtyfour::ElementRect;
+
+    static TEST_HTTP: Lazy<ProviderHttp> = Lazy::new(|| ProviderHttp::new(HttpMode::Live, FixtureStore::new("fixtures")));
+
+    #[test]
+    fn search_without_a_search_page_expires_the_session() {
+        let client = NetflixClient::new(&TEST_HTTP, NetflixProfile::default(), QueryRegistry::default(), CookieJar::default());
+        let found = JsonGraph::from_response(json!({ "jsonGraph": { "searchPage": { "abc": { "0": {} } } } }));
+        assert_eq!(search_id(&client, &found).unwrap(), "abc");
+
+        let logged_out = JsonGraph::from_response(json!({ "jsonGraph": { "search": {} } }));
+        let err = search_id(&client, &logged_out).unwrap_err();
+        assert!(matches!(err, NetflixError::SessionExpired(_)));
+        assert!(matches!(client.session(), SessionState::NeedsReauth { .. }));
+    }
+
+    #[test]
+    fn repeated_logged_out_searches_change_the_session_once() {
+        let client = NetflixClient::new(&TEST_HTTP, NetflixProfile::default(), QueryRegistry::default(), CookieJar::default());
+        let mut updates = client.subscribe();
+        let mut changes = Vec::new();
+        for _ in 0..2 {
+            // What path_evaluator does with the 200, then what search does with the graph.
+            client.check_session(&HttpResponse {
+                status: reqwest::StatusCode::OK,
+                headers: Vec::new(),
+                body: br#"{"jsonGraph":{"search":{}}}"#.to_vec(),
+            }).unwrap();
+            let graph = JsonGraph::from_response(json!({ "jsonGraph": { "search": {} } }));
+            assert!(search_id(&client, &graph).is_err());
+            if updates.has_changed().unwrap() {
+                changes.push(updates.borrow_and_update().clone());
+            }
+        }
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(changes[0], SessionState::NeedsReauth { .. }));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn kind_lookups_overlap_and_survive_failures() {
+        let ids: Vec<String> = (1..=9).map(|id| id.to_string()).collect();
+        let started = tokio::time::Instant::now();
+        let kinds = lookup_kinds(&ids, |id| async move {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            if id == "3" {
+                return Err("PersistedQueryNotFound".into());
+            }
+            Ok(ShowResult { show_data: json!({ "videoId": id }), is_show: true })
+        })
+        .await;
+
+        assert_eq!(started.elapsed(), std::time::Duration::from_millis(300));
+        assert_eq!(kinds.len(), 9);
+        assert_eq!(kinds[8].show_data["videoId"], "9");
+        assert!(!kinds[2].is_show);
+        assert_eq!(kinds[2].show_data["kind"], "unknown");
+    }
+
+    #[tokio::test]
+    async fn profile_gate_picks_the_configured_profile() {
+        let browser = Arc::new(FakeBrowser::new());
+        browser.script_result("querySelectorAll", json!(["Kids", "Movie Night"]));
+        let netflix = Netflix { driver: browser.clone(), profile_name: Some("movie night".to_string()) };
+        netflix.pass_profile_gate("https://netflix.com/watch/1").await.unwrap();
+        assert_eq!(browser.calls()[1..], [
+            BrowserCall::Click(Locator::css(".list-profiles li.profile:nth-child(2) a.profile-link")),
+            BrowserCall::Goto("https://netflix.com/watch/1".to_string()),
+        ]);
+
+        let missing = Netflix { driver: browser.clone(), profile_name: Some("Guest".to_string()) };
+        let err = missing.pass_profile_gate("https://netflix.com/watch/1").await.unwrap_err();
+        assert!(err.to_string().contains("Kids, Movie Night"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn browser_gets_the_shared_jar() {
+        let browser = FakeBrowser::new();
+        let jar = CookieJar::parse_header("NetflixId=abc; SecureNetflixId=def", "www.netflix.com");
+        add_cookies(&browser, jar.cookies()).await.unwrap();
+        assert_eq!(browser.calls(), vec![
+            BrowserCall::AddCookie("NetflixId".to_string()),
+            BrowserCall::AddCookie("SecureNetflixId".to_string()),
+        ]);
+        let cookies = browser.get_all_cookies().await.unwrap();
+        assert_eq!(cookies[0].domain.as_deref(), Some(".netflix.com"));
+    }
+
+    #[tokio::test]
+    async fn no_gate_means_no_clicks() {
+        let browser = Arc::new(FakeBrowser::new());
+        Netflix::new(browser.clone()).pass_profile_gate("https://netflix.com/watch/1").await.unwrap();
+        assert_eq!(browser.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn pause_clicks_the_middle_of_the_player() {
+        let browser = Arc::new(FakeBrowser::new());
+        Netflix::new(browser.clone()).pause().await.unwrap();
+        assert_eq!(browser.calls(), vec![
+            BrowserCall::MoveMouse(959, 539),
+            BrowserCall::MoveMouse(960, 540),
+            BrowserCall::ClickAt(960, 540),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn skip_to_clicks_the_timeline_at_the_target_time() {
+        let browser = Arc::new(FakeBrowser::new());
+        let bar = Locator::css("div[data-uia='timeline-bar']");
+        browser.set_attr(Locator::css("button[data-uia='timeline-knob']"), "aria-valuemax", "7200");
+        browser.set_rect(bar.clone(), ElementRect { x: 100.0, y: 900.0, width: 1000.0, height: 10.0 });
+
+        Netflix::new(browser.clone()).skip_to_specific_timeline("0.30").await.unwrap();
+        assert_eq!(browser.calls().last(), Some(&BrowserCall::ClickAtOffset(bar, -250, 0)));
+    }
+
+    #[tokio::test]
+    async fn skip_to_fails_without_a_timeline() {
+        let browser = Arc::new(FakeBrowser::new());
+        browser.remove(Locator::css("div[data-uia='timeline-bar']"));
+        assert!(Netflix::new(browser.clone()).skip_to_specific_timeline("0.30").await.is_err());
+        assert!(Netflix::new(browser).skip_to_specific_timeline("1.75").await.is_err());
+    }
+}
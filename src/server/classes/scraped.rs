@@ -0,0 +1,497 @@
+//! Providers that are nothing more than a search page and a few CSS selectors, defined
+//! in `[[scrapers]]` tables so a layout change or a new site is a config change.
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::server::{config::CONFIG, http::HTTP};
+
+use super::{netflix::ShowResult, Provider, SearchResult};
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Built in so Fasel works without a config file; a `[[scrapers]]` entry named
+/// "Fasel" replaces it.
+const FASEL: &str = r##"
+name = "Fasel"
+search_url = "https://web184.faselhd.cafe/?s={query}"
+result = "div.postDiv"
+title = "div.h1"
+poster = "div.imgdiv-class img"
+poster_attr = "data-src"
+title_cleanup = '[\x{0600}-\x{06FF}]+'
+
+[series]
+url_pattern = '/(seasons|series)/'
+page_url = "https://web184.faselhd.cafe/?p={id}"
+id_pattern = '\?p=(\d+)'
+show_id = "link[rel='shortlink']"
+season = "#seasonList .seasonDiv"
+season_attr = "onclick"
+season_title = ".title"
+episode = "#epAll a"
+"##;
+
+/// Every scraped provider: the built-in ones, overridden or extended by the config.
+/// Definitions that don't compile are logged and left out.
+pub static SCRAPERS: Lazy<Vec<ScrapedProvider>> = Lazy::new(|| {
+    let builtin: ScraperConfig = toml::from_str(FASEL).expect("built-in Fasel scraper");
+    let mut configs = vec![builtin];
+    for config in &CONFIG.scrapers {
+        configs.retain(|existing| !existing.name.eq_ignore_ascii_case(&config.name));
+        configs.push(config.clone());
+    }
+    configs
+        .into_iter()
+        .filter_map(|config| match ScrapedProvider::new(config) {
+            Ok(provider) => Some(provider),
+            Err(err) => {
+                eprintln!("Skipping scraper: {}", err);
+                None
+            }
+        })
+        .collect()
+});
+
+pub fn find(name: &str) -> Option<&'static ScrapedProvider> {
+    SCRAPERS.iter().find(|provider| provider.config.name.eq_ignore_ascii_case(name))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScraperConfig {
+    pub name: String,
+    /// `{query}` is replaced with the url-encoded search.
+    pub search_url: String,
+    /// One element per search hit; the selectors below are relative to it.
+    pub result: String,
+    #[serde(default = "default_link")]
+    pub link: String,
+    #[serde(default = "default_href")]
+    pub link_attr: String,
+    pub title: String,
+    /// Read the title from this attribute instead of the element's text.
+    #[serde(default)]
+    pub title_attr: Option<String>,
+    pub poster: String,
+    #[serde(default = "default_src")]
+    pub poster_attr: String,
+    /// Removed from titles, e.g. the Arabic half of a bilingual title.
+    #[serde(default)]
+    pub title_cleanup: Option<String>,
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+    /// A saved search page for `check-scrapers` to validate the selectors against.
+    #[serde(default)]
+    pub fixture: Option<String>,
+    #[serde(default)]
+    pub series: Option<SeriesConfig>,
+}
+
+/// How to find a series' seasons and episodes. Seasons and shows are addressed by a
+/// numeric id, pulled out of links with `id_pattern` and put back into `page_url`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SeriesConfig {
+    /// Matched against result links to tell series from movies.
+    pub url_pattern: String,
+    /// `{id}` is replaced with a show or season id.
+    pub page_url: String,
+    /// Its first capture group is the id.
+    pub id_pattern: String,
+    /// The element carrying the page's own id.
+    pub show_id: String,
+    #[serde(default = "default_href")]
+    pub show_id_attr: String,
+    /// One element per season; a page without any is a single-season series.
+    pub season: String,
+    #[serde(default = "default_href")]
+    pub season_attr: String,
+    #[serde(default)]
+    pub season_title: Option<String>,
+    pub episode: String,
+    #[serde(default = "default_href")]
+    pub episode_attr: String,
+    /// Its first match in the episode text is the episode number.
+    #[serde(default = "default_episode_number")]
+    pub episode_number: String,
+}
+
+fn default_link() -> String {
+    "a".to_string()
+}
+
+fn default_href() -> String {
+    "href".to_string()
+}
+
+fn default_src() -> String {
+    "src".to_string()
+}
+
+fn default_max_results() -> usize {
+    9
+}
+
+fn default_episode_number() -> String {
+    r"\d+".to_string()
+}
+
+fn selector(name: &str, field: &str, css: &str) -> Result<Selector, Error> {
+    Selector::parse(css).map_err(|e| format!("{}: bad {} selector '{}': {:?}", name, field, css, e).into())
+}
+
+fn regex(name: &str, field: &str, pattern: &str) -> Result<Regex, Error> {
+    Regex::new(pattern).map_err(|e| format!("{}: bad {} pattern: {}", name, field, e).into())
+}
+
+fn text(element: ElementRef) -> String {
+    element.text().collect::<String>().trim().to_string()
+}
+
+struct Series {
+    url_pattern: Regex,
+    id_pattern: Regex,
+    show_id: Selector,
+    season: Selector,
+    season_title: Option<Selector>,
+    episode: Selector,
+    episode_number: Regex,
+}
+
+/// A `ScraperConfig` with its selectors and patterns compiled.
+pub struct ScrapedProvider {
+    name: &'static str,
+    config: ScraperConfig,
+    result: Selector,
+    link: Selector,
+    title: Selector,
+    poster: Selector,
+    title_cleanup: Option<Regex>,
+    series: Option<Series>,
+}
+
+impl ScrapedProvider {
+    pub fn new(config: ScraperConfig) -> Result<Self, Error> {
+        let name = config.name.as_str();
+        if !config.search_url.contains("{query}") {
+            return Err(format!("{}: search_url has no {{query}}", name).into());
+        }
+        let series = match &config.series {
+            Some(series) if !series.page_url.contains("{id}") => {
+                return Err(format!("{}: series.page_url has no {{id}}", name).into())
+            }
+            Some(series) => Some(Series {
+                url_pattern: regex(name, "series.url_pattern", &series.url_pattern)?,
+                id_pattern: regex(name, "series.id_pattern", &series.id_pattern)?,
+                show_id: selector(name, "series.show_id", &series.show_id)?,
+                season: selector(name, "series.season", &series.season)?,
+                season_title: series.season_title.as_deref().map(|css| selector(name, "series.season_title", css)).transpose()?,
+                episode: selector(name, "series.episode", &series.episode)?,
+                episode_number: regex(name, "series.episode_number", &series.episode_number)?,
+            }),
+            None => None,
+        };
+        Ok(ScrapedProvider {
+            // Providers are built once and live in `SCRAPERS` for the life of the program.
+            name: Box::leak(config.name.clone().into_boxed_str()),
+            result: selector(name, "result", &config.result)?,
+            link: selector(name, "link", &config.link)?,
+            title: selector(name, "title", &config.title)?,
+            poster: selector(name, "poster", &config.poster)?,
+            title_cleanup: config.title_cleanup.as_deref().map(|pattern| regex(name, "title_cleanup", pattern)).transpose()?,
+            series,
+            config,
+        })
+    }
+
+    pub fn config(&self) -> &ScraperConfig {
+        &self.config
+    }
+
+    /// Runs the search selectors over a saved page, failing unless every result has
+    /// a link and a title. Returns how many results were found.
+    pub fn check(&self, html: &str) -> Result<usize, Error> {
+        let document = Html::parse_document(html);
+        let results: Vec<ElementRef> = document.select(&self.result).collect();
+        if results.is_empty() {
+            return Err(format!("{}: '{}' matched nothing", self.config.name, self.config.result).into());
+        }
+        for (index, result) in results.iter().enumerate() {
+            if self.parse_result(*result).is_none() {
+                return Err(format!("{}: result {} has no link or title", self.config.name, index + 1).into());
+            }
+        }
+        Ok(results.len())
+    }
+
+    fn parse_result(&self, result: ElementRef) -> Option<SearchResult> {
+        let url = result.select(&self.link).next()?.value().attr(&self.config.link_attr)?.to_string();
+        let title_element = result.select(&self.title).next()?;
+        let title = match &self.config.title_attr {
+            Some(attr) => title_element.value().attr(attr)?.trim().to_string(),
+            None => text(title_element),
+        };
+        let title = match &self.title_cleanup {
+            Some(cleanup) => cleanup.replace_all(&title, "").trim().to_string(),
+            None => title,
+        };
+        let poster = result
+            .select(&self.poster)
+            .next()
+            .and_then(|poster| poster.value().attr(&self.config.poster_attr))
+            .unwrap_or_default()
+            .to_string();
+        Some((url, poster, title, ShowResult { show_data: json!({ "movie_id": "0" }), is_show: false }))
+    }
+
+    pub fn parse_search(&self, html: &str) -> Vec<SearchResult> {
+        let document = Html::parse_document(html);
+        document
+            .select(&self.result)
+            .filter_map(|result| self.parse_result(result))
+            .take(self.config.max_results)
+            .collect()
+    }
+
+    fn is_series(&self, url: &str) -> bool {
+        self.series.as_ref().is_some_and(|series| series.url_pattern.is_match(url))
+    }
+
+    fn id_in(&self, text: &str) -> Option<u64> {
+        let captures = self.series.as_ref()?.id_pattern.captures(text)?;
+        captures.get(1)?.as_str().parse().ok()
+    }
+
+    /// A series page's seasons, in the shape the season menu reads.
+    pub fn parse_series(&self, html: &str) -> Option<ShowResult> {
+        let (series, config) = (self.series.as_ref()?, self.config.series.as_ref()?);
+        let document = Html::parse_document(html);
+        let show_id = document
+            .select(&series.show_id)
+            .next()
+            .and_then(|element| element.value().attr(&config.show_id_attr))
+            .and_then(|attr| self.id_in(attr))?;
+        let mut seasons: Vec<Value> = document
+            .select(&series.season)
+            .enumerate()
+            .filter_map(|(index, season)| {
+                let id = self.id_in(season.value().attr(&config.season_attr)?)?;
+                let title = series
+                    .season_title
+                    .as_ref()
+                    .and_then(|title| season.select(title).next())
+                    .map(text)
+                    .filter(|title| !title.is_empty())
+                    .unwrap_or_else(|| format!("Season {}", index + 1));
+                Some(json!({ "node": { "title": title, "videoId": id } }))
+            })
+            .collect();
+        if seasons.is_empty() {
+            document.select(&series.episode).next()?;
+            seasons.push(json!({ "node": { "title": "Season 1", "videoId": show_id } }));
+        }
+        Some(ShowResult { show_data: json!({ "videoId": show_id, "seasons": { "edges": seasons } }), is_show: true })
+    }
+
+    /// `(number, title, url)` for a season page's episodes, numbered by position when
+    /// the text has no number.
+    pub fn parse_episodes(&self, html: &str) -> Vec<(i64, String, String)> {
+        let (Some(series), Some(config)) = (&self.series, &self.config.series) else { return Vec::new() };
+        let document = Html::parse_document(html);
+        document
+            .select(&series.episode)
+            .enumerate()
+            .filter_map(|(index, episode)| {
+                let url = episode.value().attr(&config.episode_attr)?.to_string();
+                let title = text(episode);
+                let number = series
+                    .episode_number
+                    .find(&title)
+                    .and_then(|digits| digits.as_str().parse().ok())
+                    .unwrap_or(index as i64 + 1);
+                Some((number, title, url))
+            })
+            .collect()
+    }
+
+    /// `(number, title, id)` for the episode menu. An episode is identified by its position
+    /// on the season page, as numbers parsed from titles can repeat; the number is only shown.
+    fn episode_options(&self, html: &str) -> Vec<(i64, String, i64)> {
+        self.parse_episodes(html)
+            .into_iter()
+            .enumerate()
+            .map(|(position, (number, title, _))| (number, title, position as i64))
+            .collect()
+    }
+
+    fn episode_at(&self, html: &str, episode_id: &str) -> Option<String> {
+        let position: usize = episode_id.parse().ok()?;
+        self.parse_episodes(html).into_iter().nth(position).map(|(_, _, url)| url)
+    }
+
+    async fn page(&self, url: &str) -> Result<String, Error> {
+        Ok(HTTP.send(&self.config.name.to_lowercase(), HTTP.client().get(url)).await?.text())
+    }
+
+    async fn search_page(&self, query: &str) -> Result<String, Error> {
+        self.page(&self.config.search_url.replace("{query}", &urlencoding::encode(query))).await
+    }
+
+    async fn series_page(&self, id: &str) -> Result<String, Error> {
+        let config = self.config.series.as_ref().ok_or_else(|| format!("{} has no series", self.config.name))?;
+        self.page(&config.page_url.replace("{id}", id)).await
+    }
+}
+
+#[async_trait]
+impl Provider for ScrapedProvider {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchResult>, Error> {
+        let mut results = self.parse_search(&self.search_page(query).await?);
+
+        // Series pages list their seasons, so read them up front like Netflix's kind lookups.
+        let shows = join_all(results.iter().map(|(url, _, _, _)| async move {
+            if !self.is_series(url) {
+                return None;
+            }
+            match self.page(url).await {
+                Ok(html) => self.parse_series(&html),
+                Err(e) => {
+                    eprintln!("Failed to read {} series {}: {}", self.config.name, url, e);
+                    None
+                }
+            }
+        }))
+        .await;
+        for (result, show) in results.iter_mut().zip(shows) {
+            if let Some(show) = show {
+                result.3 = show;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Skips reading the series pages, which `/info` doesn't need.
+    async fn best_match(&self, query: &str) -> Result<Option<(String, String)>, Error> {
+        Ok(self.parse_search(&self.search_page(query).await?).into_iter().next().map(|(url, image, _, _)| (url, image)))
+    }
+
+    async fn show(&self, show_id: &str) -> Result<ShowResult, Error> {
+        self.parse_series(&self.series_page(show_id).await?)
+            .ok_or_else(|| format!("{} page {} isn't a series", self.config.name, show_id).into())
+    }
+
+    async fn episodes(&self, season_id: &str) -> Result<Vec<(i64, String, i64)>, Error> {
+        Ok(self.episode_options(&self.series_page(season_id).await?))
+    }
+
+    async fn episode_url(&self, season_id: &str, episode_id: &str) -> Result<String, Error> {
+        self.episode_at(&self.series_page(season_id).await?, episode_id)
+            .ok_or_else(|| format!("Episode {} isn't in {} season {}", episode_id, self.config.name, season_id).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fasel() -> ScrapedProvider {
+        ScrapedProvider::new(toml::from_str(FASEL).unwrap()).unwrap()
+    }
+
+    const SEARCH: &str = r#"<html><body>
+        <div class="postDiv"><a href="https://web184.faselhd.cafe/movies/dark-knight">
+            <div class="imgdiv-class"><img data-src="https://img.example/dark-knight.jpg"></div>
+            <div class="h1">فيلم The Dark Knight</div>
+        </a></div>
+        <div class="postDiv"><a href="https://web184.faselhd.cafe/seasons/dark">
+            <div class="imgdiv-class"><img data-src="https://img.example/dark.jpg"></div>
+            <div class="h1">مسلسل Dark</div>
+        </a></div>
+    </body></html>"#;
+
+    const SERIES: &str = r#"<html><head><link rel='shortlink' href='https://web184.faselhd.cafe/?p=262510' /></head><body>
+        <div id="seasonList">
+            <div class="seasonDiv active" onclick="window.location.href = '/?p=262515'"><div class="title">موسم 1</div></div>
+            <div class="seasonDiv" onclick="window.location.href = '/?p=298870'"><div class="title"></div></div>
+        </div>
+        <div id="epAll">
+            <a href="https://web184.faselhd.cafe/episodes/show-1">الحلقة 1</a>
+            <a href="https://web184.faselhd.cafe/episodes/show-2">الحلقة 2</a>
+            <a href="https://web184.faselhd.cafe/episodes/show-special">Special</a>
+        </div>
+    </body></html>"#;
+
+    #[test]
+    fn builtin_fasel_reads_search_results() {
+        let fasel = fasel();
+        assert_eq!(fasel.check(SEARCH).unwrap(), 2);
+        let results = fasel.parse_search(SEARCH);
+        assert_eq!(results[0].0, "https://web184.faselhd.cafe/movies/dark-knight");
+        assert_eq!(results[0].1, "https://img.example/dark-knight.jpg");
+        assert_eq!(results[1].2, "Dark");
+        assert!(fasel.is_series(&results[1].0));
+        assert!(!fasel.is_series(&results[0].0));
+        assert!(fasel.check("<html></html>").is_err());
+    }
+
+    #[test]
+    fn parses_series_seasons_and_episodes() {
+        let fasel = fasel();
+        let show = fasel.parse_series(SERIES).unwrap();
+        assert!(show.is_show);
+        assert_eq!(show.show_data["videoId"], 262510);
+        let seasons = &show.show_data["seasons"]["edges"];
+        assert_eq!(seasons[0]["node"]["title"], "موسم 1");
+        assert_eq!(seasons[1]["node"]["title"], "Season 2");
+        assert_eq!(seasons[1]["node"]["videoId"], 298870);
+
+        let episodes = fasel.parse_episodes(SERIES);
+        assert_eq!(episodes[1], (2, "الحلقة 2".to_string(), "https://web184.faselhd.cafe/episodes/show-2".to_string()));
+        assert_eq!(episodes[2].0, 3);
+
+        let single = SERIES.replace("seasonList", "nothing");
+        let show = fasel.parse_series(&single).unwrap();
+        assert_eq!(show.show_data["seasons"]["edges"][0]["node"]["videoId"], 262510);
+        assert!(fasel.parse_series("<html><link rel='shortlink' href='/?p=1'></html>").is_none());
+    }
+
+    #[test]
+    fn episodes_are_picked_by_position_not_number() {
+        let fasel = fasel();
+        // The second "1" repeats a number and "Special" falls back to 3, the same as the last one.
+        let page = SERIES.replace("الحلقة 2", "الحلقة 1").replace("</div>\n    </body>", "<a href=\"https://web184.faselhd.cafe/episodes/show-3\">الحلقة 3</a></div>\n    </body>");
+        let options = fasel.episode_options(&page);
+        let numbers: Vec<i64> = options.iter().map(|(number, _, _)| *number).collect();
+        assert_eq!(numbers, vec![1, 1, 3, 3]);
+        let ids: Vec<i64> = options.iter().map(|(_, _, id)| *id).collect();
+        assert_eq!(ids, vec![0, 1, 2, 3]);
+
+        assert_eq!(fasel.episode_at(&page, "1").as_deref(), Some("https://web184.faselhd.cafe/episodes/show-2"));
+        assert_eq!(fasel.episode_at(&page, "3").as_deref(), Some("https://web184.faselhd.cafe/episodes/show-3"));
+        assert!(fasel.episode_at(&page, "9").is_none());
+    }
+
+    #[test]
+    fn rejects_broken_definitions() {
+        let config = |extra: &str| -> ScraperConfig {
+            toml::from_str(&format!(
+                "name = \"Test\"\nresult = \"li\"\ntitle = \"h2\"\nposter = \"img\"\n{}",
+                extra
+            ))
+            .unwrap()
+        };
+        assert!(ScrapedProvider::new(config("search_url = \"https://example.com/?q={query}\"")).is_ok());
+        let err = ScrapedProvider::new(config("search_url = \"https://example.com/\"")).err().unwrap();
+        assert_eq!(err.to_string(), "Test: search_url has no {query}");
+        let err = ScrapedProvider::new(config("search_url = \"{query}\"\nlink = \"a[\"")).err().unwrap();
+        assert!(err.to_string().starts_with("Test: bad link selector 'a['"), "{}", err);
+    }
+}
@@ -0,0 +1,490 @@
+use std::fmt;
+
+use futures::{stream, StreamExt, TryStreamExt};
+use once_cell::sync::Lazy;
+use reqwest::{RequestBuilder, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+
+use crate::server::{
+    classes::TitleDetails,
+    config::CONFIG,
+    http::{HttpResponse, ProviderHttp, HTTP},
+};
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+const API_URL: &str = "https://api3.shahid.net/proxy/v2.1";
+/// Size the image templates are filled in with, a 1.5x version of a collage tile.
+const POSTER_SIZE: (u32, u32) = (375, 525);
+/// Results asked for per search; the select menus only show the first few anyway.
+const SEARCH_PAGE_SIZE: u32 = 24;
+/// Products asked for per playlist page; a 90 episode series fits in two.
+const PLAYLIST_PAGE_SIZE: u32 = 50;
+/// Stops paging a playlist after this many pages, in case it never comes back short.
+const MAX_PAGES: u32 = 40;
+/// How many playlist pages are fetched at once once the total is known.
+const PLAYLIST_FETCHES: usize = 4;
+
+pub static SHAHID: Lazy<ShahidClient> =
+    Lazy::new(|| ShahidClient::new(&HTTP, &CONFIG.shahid.country, &CONFIG.shahid.language));
+
+#[derive(Debug)]
+pub enum ShahidError {
+    /// The API answered with a non-success status, with whatever faults it listed.
+    Status { endpoint: &'static str, status: StatusCode, faults: Vec<String> },
+    /// A 200 that carries faults instead of results.
+    Api { endpoint: &'static str, faults: Vec<String> },
+    /// The response didn't have the shape the endpoint is expected to return.
+    Schema { endpoint: &'static str, source: serde_json::Error },
+}
+
+impl fmt::Display for ShahidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShahidError::Status { endpoint, status, faults } if faults.is_empty() => {
+                write!(f, "Shahid {} returned HTTP {}", endpoint, status.as_u16())
+            }
+            ShahidError::Status { endpoint, status, faults } => {
+                write!(f, "Shahid {} returned HTTP {}: {}", endpoint, status.as_u16(), faults.join("; "))
+            }
+            ShahidError::Api { endpoint, faults } => write!(f, "Shahid {} failed: {}", endpoint, faults.join("; ")),
+            ShahidError::Schema { endpoint, source } => {
+                write!(f, "Shahid {} response didn't match the expected schema: {}", endpoint, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShahidError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ShahidError::Schema { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Shahid sends most ids as numbers but playlist ids, which don't fit in 64 bits, as strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Id {
+    String(String),
+    Number(serde_json::Number),
+}
+
+impl From<Id> for String {
+    fn from(id: Id) -> Self {
+        match id {
+            Id::String(id) => id,
+            Id::Number(id) => id.to_string(),
+        }
+    }
+}
+
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Id::deserialize(deserializer).map(String::from)
+}
+
+fn optional_string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(Option::<Id>::deserialize(deserializer)?.map(String::from))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductListResponse {
+    product_list: Option<ProductList>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductResponse {
+    product_model: Product,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductList {
+    #[serde(default)]
+    pub products: Vec<Product>,
+    #[serde(default)]
+    pub has_more: Option<bool>,
+    /// Products in the whole playlist, not just this page.
+    #[serde(default)]
+    pub count: Option<u32>,
+}
+
+impl ProductList {
+    /// A short page, or one Shahid says is the last, ends the playlist.
+    fn is_last_page(&self) -> bool {
+        self.products.len() < PLAYLIST_PAGE_SIZE as usize || self.has_more == Some(false)
+    }
+
+    /// The pages after this first one, when the playlist says how long it is.
+    fn remaining_pages(&self) -> Option<std::ops::Range<u32>> {
+        let count = self.count?;
+        if self.is_last_page() {
+            return Some(1..1);
+        }
+        Some(1..count.div_ceil(PLAYLIST_PAGE_SIZE).clamp(1, MAX_PAGES))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Product {
+    #[serde(deserialize_with = "string_or_number")]
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default, alias = "type")]
+    pub product_type: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub product_url: Option<ProductUrl>,
+    /// Episode number, for products listed in a playlist.
+    #[serde(default)]
+    pub number: Option<u32>,
+    /// The season the product was found through, with its playlists.
+    #[serde(default)]
+    pub season: Option<Season>,
+    /// Every season of a show; only filled in by `ShahidClient::product`.
+    #[serde(default)]
+    pub seasons: Vec<Season>,
+    #[serde(default)]
+    pub image: Option<ProductImage>,
+    /// `2023-03-21`, or sometimes just the year.
+    #[serde(default)]
+    pub production_date: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<Genre>,
+}
+
+impl Product {
+    pub fn is_show(&self) -> bool {
+        self.product_type.as_deref().is_some_and(|kind| kind.eq_ignore_ascii_case("show"))
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.product_url.as_ref().map(|url| url.url.as_str()).filter(|url| !url.is_empty())
+    }
+
+    /// The portrait poster for the collage, falling back to the landscape thumbnail.
+    pub fn poster_url(&self) -> Option<String> {
+        let image = self.image.as_ref()?;
+        let template = image.poster_image.as_deref().or(image.thumbnail_image.as_deref()).filter(|url| !url.is_empty())?;
+        let (width, height) = POSTER_SIZE;
+        Some(
+            template
+                .replace("{width}", &width.to_string())
+                .replace("{height}", &height.to_string())
+                .replace("{croppingPoint}", "mc"),
+        )
+    }
+
+    pub fn details(&self) -> TitleDetails {
+        // A show's own product lists every season; a search hit only carries the current one.
+        let episodes = match self.seasons.is_empty() {
+            false => self.seasons.iter().map(|season| season.number_of_episodes).sum(),
+            true => self.season.as_ref().and_then(|season| season.number_of_episodes),
+        };
+        TitleDetails {
+            title: self.title.clone(),
+            synopsis: self.description.clone().filter(|description| !description.is_empty()),
+            year: self.production_date.as_deref().and_then(|date| date.get(..4)).and_then(|year| year.parse().ok()),
+            seasons: Some(self.seasons.len() as u32).filter(|count| *count > 0),
+            episodes: episodes.filter(|count| *count > 0 && self.is_show()),
+            genres: self.genres.iter().map(|genre| genre.title.clone()).collect(),
+            ..TitleDetails::default()
+        }
+    }
+}
+
+/// Image URLs are templates with `{width}`, `{height}` and `{croppingPoint}` placeholders.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductImage {
+    #[serde(default)]
+    pub poster_image: Option<String>,
+    #[serde(default)]
+    pub thumbnail_image: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Genre {
+    #[serde(default)]
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductUrl {
+    #[serde(default)]
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Season {
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub id: String,
+    #[serde(default, deserialize_with = "optional_string_or_number")]
+    pub season_number: Option<String>,
+    #[serde(default)]
+    pub season_name: Option<String>,
+    #[serde(default)]
+    pub number_of_episodes: Option<u32>,
+    #[serde(default)]
+    pub playlists: Vec<Playlist>,
+}
+
+impl Season {
+    pub fn label(&self) -> String {
+        match (&self.season_name, &self.season_number) {
+            (Some(name), _) if !name.is_empty() => name.clone(),
+            (_, Some(number)) => format!("Season {}", number),
+            _ => "Season".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Playlist {
+    #[serde(deserialize_with = "string_or_number")]
+    pub id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default, rename = "type")]
+    pub playlist_type: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FaultResponse {
+    #[serde(default)]
+    faults: Vec<Fault>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Fault {
+    #[serde(default)]
+    user_message: Option<String>,
+    #[serde(default)]
+    internal_message: Option<String>,
+}
+
+/// Maps the status and any `faults` to a `ShahidError`, otherwise deserializes the body.
+fn parse<T: DeserializeOwned>(endpoint: &'static str, response: &HttpResponse) -> Result<T, ShahidError> {
+    let faults: Vec<String> = response
+        .json::<FaultResponse>()
+        .unwrap_or_default()
+        .faults
+        .into_iter()
+        .filter_map(|fault| fault.user_message.or(fault.internal_message))
+        .collect();
+    if !response.status.is_success() {
+        return Err(ShahidError::Status { endpoint, status: response.status, faults });
+    }
+    if !faults.is_empty() {
+        return Err(ShahidError::Api { endpoint, faults });
+    }
+    response.json().map_err(|source| ShahidError::Schema { endpoint, source })
+}
+
+/// One pooled HTTP client for the Shahid API, sending the country and language
+/// every request is scoped to.
+pub struct ShahidClient {
+    http: &'static ProviderHttp,
+    country: String,
+    language: String,
+}
+
+impl ShahidClient {
+    pub fn new(http: &'static ProviderHttp, country: &str, language: &str) -> Self {
+        ShahidClient { http, country: country.to_uppercase(), language: language.to_lowercase() }
+    }
+
+    fn request<R: Serialize>(&self, endpoint: &str, request: &R, extra_query: &str) -> Result<RequestBuilder, Error> {
+        let url = format!(
+            "{}/{}?request={}{}&country={}",
+            API_URL,
+            endpoint,
+            urlencoding::encode(&serde_json::to_string(request)?),
+            extra_query,
+            self.country
+        );
+        Ok(self
+            .http
+            .client()
+            .get(url)
+            .header("accept", "application/json, text/plain, */*")
+            .header("accept-language", &self.language)
+            .header("language", self.language.to_uppercase())
+            .header("browser_name", "CHROME")
+            .header("browser_version", "114.0.0.0")
+            .header("os_version", "NT 10.0")
+            .header("shahid_os", "WEB")
+            .header("referer", "https://shahid.mbc.net/"))
+    }
+
+    async fn get<R: Serialize, T: DeserializeOwned>(&self, endpoint: &'static str, request: &R, extra_query: &str) -> Result<T, Error> {
+        let response = self.http.send("shahid", self.request(endpoint, request, extra_query)?).await?;
+        Ok(parse(endpoint, &response)?)
+    }
+
+    /// Titles matching `query`; an empty list when nothing matches.
+    pub async fn search(&self, query: &str) -> Result<Vec<Product>, Error> {
+        let request = serde_json::json!({ "name": query, "pageNumber": 0, "pageSize": SEARCH_PAGE_SIZE });
+        let body: ProductListResponse = self.get("t-search", &request, "&exactMatch=false").await?;
+        Ok(body.product_list.unwrap_or_default().products)
+    }
+
+    /// The full product for a show, movie or season id. For a show this lists every
+    /// season; for a season id, `season` carries that season's playlists.
+    pub async fn product(&self, id: &str) -> Result<Product, Error> {
+        let body: ProductResponse = self.get("product/id", &serde_json::json!({ "id": id }), "").await?;
+        Ok(body.product_model)
+    }
+
+    pub async fn playlist_page(&self, playlist_id: &str, page: u32) -> Result<ProductList, Error> {
+        let request = serde_json::json!({
+            "pageNumber": page,
+            "pageSize": PLAYLIST_PAGE_SIZE,
+            "playListId": playlist_id,
+            "sorts": [{ "order": "DESC", "type": "SORTDATE" }],
+            "isDynamicPlaylist": false
+        });
+        let body: ProductListResponse = self.get("product/playlist", &request, "").await?;
+        Ok(body.product_list.unwrap_or_default())
+    }
+
+    /// Every product in the playlist. When the first page gives the total, the rest
+    /// are fetched concurrently; otherwise pages are read in turn until a short one.
+    /// A failing page fails the whole call rather than returning the pages before it.
+    pub async fn playlist(&self, playlist_id: &str) -> Result<Vec<Product>, Error> {
+        let first = self.playlist_page(playlist_id, 0).await?;
+        if let Some(pages) = first.remaining_pages() {
+            let rest: Vec<ProductList> = stream::iter(pages)
+                .map(|page| self.playlist_page(playlist_id, page))
+                .buffered(PLAYLIST_FETCHES)
+                .try_collect()
+                .await?;
+            return Ok(first.products.into_iter().chain(rest.into_iter().flat_map(|list| list.products)).collect());
+        }
+
+        let mut last = first.is_last_page();
+        let mut products = first.products;
+        for page in 1..MAX_PAGES {
+            if last {
+                break;
+            }
+            let list = self.playlist_page(playlist_id, page).await?;
+            last = list.is_last_page();
+            products.extend(list.products);
+        }
+        Ok(products)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::http::{FixtureStore, HttpMode};
+
+    static TEST_HTTP: Lazy<ProviderHttp> = Lazy::new(|| ProviderHttp::new(HttpMode::Live, FixtureStore::new("fixtures")));
+
+    fn response(status: u16, body: &str) -> HttpResponse {
+        HttpResponse {
+            status: StatusCode::from_u16(status).unwrap(),
+            headers: Vec::new(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn requests_use_the_configured_country_and_language() {
+        let client = ShahidClient::new(&TEST_HTTP, "sa", "AR");
+        let request = client.request("t-search", &serde_json::json!({ "name": "x" }), "").unwrap().build().unwrap();
+        assert!(request.url().as_str().ends_with("&country=SA"), "{}", request.url());
+        assert_eq!(request.headers()["language"], "AR");
+        assert_eq!(request.headers()["accept-language"], "ar");
+    }
+
+    #[test]
+    fn parses_products_seasons_and_playlists() {
+        let body = r#"{ "productList": { "products": [{
+            "id": 387285, "title": "Kamel El Adad", "productType": "SHOW",
+            "season": { "id": 387290, "playlists": [
+                { "id": "4992279710951949922826573713", "title": "الحلقات", "type": "EPISODE" },
+                { "id": "4992279710951949922826573714", "title": "Clips", "type": "CLIP" }
+            ] }
+        }] } }"#;
+        let list: ProductListResponse = parse("t-search", &response(200, body)).unwrap();
+        let product = &list.product_list.unwrap().products[0];
+        assert_eq!(product.id, "387285");
+        assert!(product.is_show());
+        let playlists = &product.season.as_ref().unwrap().playlists;
+        assert_eq!(playlists[0].id, "4992279710951949922826573713");
+        assert_eq!(playlists[1].playlist_type.as_deref(), Some("CLIP"));
+
+        let body = r#"{ "productModel": { "id": 387285, "title": "Kamel El Adad", "seasons": [
+            { "id": 387290, "seasonNumber": 1, "seasonName": "" },
+            { "id": 387299, "seasonNumber": null },
+            { "id": 412044, "seasonNumber": "2", "seasonName": "Season 2: Reunion" }
+        ] } }"#;
+        let show: ProductResponse = parse("product/id", &response(200, body)).unwrap();
+        let labels: Vec<String> = show.product_model.seasons.iter().map(Season::label).collect();
+        assert_eq!(labels, vec!["Season 1", "Season", "Season 2: Reunion"]);
+    }
+
+    #[test]
+    fn reads_posters_and_details() {
+        let product: Product = serde_json::from_str(r#"{
+            "id": 387285, "title": "Kamel El Adad", "productType": "SHOW",
+            "description": "A family of ten moves into a tiny flat.", "productionDate": "2023-03-21",
+            "genres": [{ "id": 1, "title": "Comedy" }, { "id": 2, "title": "Family" }],
+            "image": {
+                "posterImage": "https://shahid.mbc.net/mediaObject/abc?height={height}&width={width}&croppingPoint={croppingPoint}&type=webp",
+                "thumbnailImage": "https://shahid.mbc.net/mediaObject/thumb"
+            },
+            "season": { "id": 387290, "seasonNumber": 1, "numberOfEpisodes": 30 }
+        }"#).unwrap();
+        assert_eq!(
+            product.poster_url().unwrap(),
+            "https://shahid.mbc.net/mediaObject/abc?height=525&width=375&croppingPoint=mc&type=webp"
+        );
+        let details = product.details();
+        assert_eq!(details.summary(), "2023 · 30 Episodes · Comedy");
+        assert_eq!(details.synopsis.as_deref(), Some("A family of ten moves into a tiny flat."));
+    }
+
+    #[test]
+    fn plans_the_remaining_playlist_pages() {
+        let page = |products: usize, count: Option<u32>| ProductList {
+            products: vec![serde_json::from_str(r#"{ "id": 1 }"#).unwrap(); products],
+            has_more: None,
+            count,
+        };
+        assert_eq!(page(50, Some(90)).remaining_pages(), Some(1..2));
+        assert_eq!(page(50, Some(100)).remaining_pages(), Some(1..2));
+        assert_eq!(page(12, Some(12)).remaining_pages(), Some(1..1));
+        assert_eq!(page(50, Some(100_000)).remaining_pages(), Some(1..MAX_PAGES));
+        assert_eq!(page(50, None).remaining_pages(), None);
+        assert!(page(49, None).is_last_page());
+    }
+
+    #[test]
+    fn maps_statuses_and_faults() {
+        let faults = r#"{ "faults": [{ "code": 4030, "userMessage": "Content not available in your region" }] }"#;
+        let err = parse::<ProductListResponse>("t-search", &response(403, faults)).unwrap_err();
+        assert_eq!(err.to_string(), "Shahid t-search returned HTTP 403: Content not available in your region");
+
+        let err = parse::<ProductListResponse>("product/playlist", &response(200, faults)).unwrap_err();
+        assert!(matches!(err, ShahidError::Api { .. }));
+
+        let err = parse::<ProductListResponse>("t-search", &response(502, "<html>")).unwrap_err();
+        assert_eq!(err.to_string(), "Shahid t-search returned HTTP 502");
+
+        let err = parse::<ProductListResponse>("t-search", &response(200, r#"{ "productList": { "products": [{}] } }"#)).unwrap_err();
+        assert!(matches!(err, ShahidError::Schema { .. }));
+    }
+}
@@ -0,0 +1,170 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thirtyfour::error::{WebDriverError, WebDriverResult};
+
+use crate::server::browser::{
+    wait::{wait_for, Condition},
+    BrowserBackend,
+};
+
+use super::{netflix::ShowResult, Provider, SearchResult, TitleDetails};
+
+pub mod client;
+use client::{Playlist, Product, Season, SHAHID};
+
+/// Covers the player page loading and the stream buffering once it has.
+const PLAYBACK_TIMEOUT: Duration = Duration::from_secs(30);
+
+const VIDEO_STATE_SCRIPT: &str = "return { position: video.currentTime, paused: video.paused };";
+
+/// Where a Shahid stream is, after a command.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct VideoState {
+    pub position: f64,
+    pub paused: bool,
+}
+
+/// Plays Shahid's player through its `<video>` element, as its control bar only
+/// renders while the mouse is over it.
+pub struct Shahid {
+    driver: Arc<dyn BrowserBackend>,
+}
+
+impl Shahid {
+    pub fn new(driver: Arc<dyn BrowserBackend>) -> Self {
+        Shahid { driver }
+    }
+
+    pub async fn start(&self, url: &str) -> WebDriverResult<()> {
+        self.driver.goto(url).await?;
+        wait_for(self.driver.as_ref(), "Shahid playback", Condition::VideoReady, PLAYBACK_TIMEOUT).await?;
+        self.run("video.play();", Vec::new()).await?;
+        Ok(())
+    }
+
+    /// Pauses a playing stream and plays a paused one.
+    pub async fn toggle(&self) -> WebDriverResult<VideoState> {
+        self.run("video.paused ? video.play() : video.pause();", Vec::new()).await
+    }
+
+    pub async fn seek_by(&self, seconds: f64) -> WebDriverResult<VideoState> {
+        self.run("video.currentTime = Math.max(0, video.currentTime + arguments[0]);", vec![json!(seconds)]).await
+    }
+
+    async fn run(&self, action: &str, args: Vec<Value>) -> WebDriverResult<VideoState> {
+        let script = format!(
+            "const video = document.querySelector('video');\nif (!video) return null;\n{}\n{}",
+            action, VIDEO_STATE_SCRIPT
+        );
+        let value = self.driver.execute(&script, args).await?;
+        serde_json::from_value(value)
+            .map_err(|_| WebDriverError::NotFound("Shahid video".to_string(), "the page has no <video>".to_string()))
+    }
+}
+
+pub struct ShahidSearcher;
+
+impl ShahidSearcher {
+    /// The first nine hits, like the other providers, so the collage stays readable.
+    pub async fn search(movie_name: &str) -> Result<Vec<SearchResult>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut results = Vec::new();
+        for product in SHAHID.search(movie_name).await?.into_iter().take(9) {
+            let url = product.url().unwrap_or_default().to_string();
+            let show_result = ShowResult {
+                is_show: product.is_show(),
+                show_data: serde_json::to_value(&product)?,
+            };
+            let poster = product.poster_url().unwrap_or_default();
+            results.push((url, poster, product.title, show_result));
+        }
+        Ok(results)
+    }
+
+    /// Details for a product url such as `https://shahid.mbc.net/en/series/kamel-el-adad/series-387285`.
+    pub async fn details(url: &str) -> Result<TitleDetails, Box<dyn std::error::Error + Send + Sync>> {
+        let id = product_id(url).ok_or_else(|| format!("No Shahid product id in {}", url))?;
+        Ok(SHAHID.product(id).await?.details())
+    }
+
+    /// Every season of a show, falling back to the one season the product came with.
+    pub async fn seasons(show_id: &str) -> Result<Vec<Season>, Box<dyn std::error::Error + Send + Sync>> {
+        let show = SHAHID.product(show_id).await?;
+        if show.seasons.is_empty() {
+            return Ok(show.season.into_iter().collect());
+        }
+        Ok(show.seasons)
+    }
+
+    /// The episodes, clips and trailers playlists of a season.
+    pub async fn playlists(season_id: &str) -> Result<Vec<Playlist>, Box<dyn std::error::Error + Send + Sync>> {
+        let season = SHAHID.product(season_id).await?.season;
+        Ok(season.map(|season| season.playlists).unwrap_or_default())
+    }
+
+    pub async fn episodes(playlist_id: &str) -> Result<Vec<Product>, Box<dyn std::error::Error + Send + Sync>> {
+        SHAHID.playlist(playlist_id).await
+    }
+}
+
+#[async_trait]
+impl Provider for ShahidSearcher {
+    fn name(&self) -> &'static str {
+        "Shahid"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchResult>, Box<dyn std::error::Error + Send + Sync>> {
+        ShahidSearcher::search(query).await
+    }
+
+    async fn details(&self, url: &str) -> Result<TitleDetails, Box<dyn std::error::Error + Send + Sync>> {
+        ShahidSearcher::details(url).await
+    }
+}
+
+/// Product urls end in `-{id}`, after the kind and a slug of the title.
+fn product_id(url: &str) -> Option<&str> {
+    let (_, id) = url.trim_end_matches('/').rsplit_once('-')?;
+    Some(id).filter(|id| !id.is_empty() && id.bytes().all(|byte| byte.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::server::browser::fake::{BrowserCall, FakeBrowser};
+
+    #[tokio::test]
+    async fn start_waits_for_the_video_then_plays_it() {
+        let browser = Arc::new(FakeBrowser::new());
+        browser.script_result("readyState >= 3", json!(true));
+        browser.script_result("video.currentTime", json!({ "position": 0.0, "paused": false }));
+        Shahid::new(browser.clone()).start("https://shahid.mbc.net/en/episode-412050").await.unwrap();
+
+        let calls = browser.calls();
+        assert_eq!(calls[0], BrowserCall::Goto("https://shahid.mbc.net/en/episode-412050".to_string()));
+        assert!(matches!(calls.last(), Some(BrowserCall::Execute(script)) if script.contains("video.play();")));
+    }
+
+    #[tokio::test]
+    async fn controls_drive_the_video_element() {
+        let browser = Arc::new(FakeBrowser::new());
+        browser.script_result("video.currentTime", json!({ "position": 70.0, "paused": true }));
+        let shahid = Shahid::new(browser.clone());
+
+        assert_eq!(shahid.seek_by(10.0).await.unwrap(), VideoState { position: 70.0, paused: true });
+        assert!(shahid.toggle().await.unwrap().paused);
+
+        let missing = Shahid::new(Arc::new(FakeBrowser::new()));
+        assert!(missing.toggle().await.is_err());
+    }
+
+    #[test]
+    fn reads_product_ids_from_urls() {
+        assert_eq!(product_id("https://shahid.mbc.net/en/series/kamel-el-adad/series-387285"), Some("387285"));
+        assert_eq!(product_id("https://shahid.mbc.net/ar/movies/فيلم/movie-49923/"), Some("49923"));
+        assert_eq!(product_id("https://shahid.mbc.net/en/series/kamel-el-adad"), None);
+    }
+}
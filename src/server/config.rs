@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::server::classes::{
+    netflix::{client::NetflixProfile, graphql::QueryRegistry},
+    scraped::ScraperConfig,
+};
+
+/// Settings read from `moviebot.toml` (or the file named by `MOVIEBOT_CONFIG`).
+/// Every section is optional and falls back to the built-in defaults.
+pub static CONFIG: Lazy<Config> = Lazy::new(Config::load);
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub netflix: NetflixConfig,
+    pub shahid: ShahidConfig,
+    pub admin: AdminConfig,
+    /// Scraped providers, as `[[scrapers]]` tables; see `ScraperConfig`.
+    pub scrapers: Vec<ScraperConfig>,
+    /// Replacement fallbacks by element, e.g. `"netflix.skip_forward" = ["button.forward"]`;
+    /// see `browser/selectors.toml` for the elements.
+    pub selectors: BTreeMap<String, Vec<String>>,
+    pub diagnostics: DiagnosticsConfig,
+    pub browser: BrowserConfig,
+}
+
+/// Where provider problems (like expired cookies) are reported.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct AdminConfig {
+    pub channel_id: Option<u64>,
+    /// Sent a DM as well as, or instead of, the channel post.
+    pub user_id: Option<u64>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct NetflixConfig {
+    pub profile: NetflixProfile,
+    /// Persisted GraphQL query ids by operation name, e.g.
+    /// `[netflix.queries.PreviewModalEpisodeSelector]` with `id` and `version`.
+    pub queries: QueryRegistry,
+    /// Profile to pick on the "Who's watching?" gate; only needed for accounts with several.
+    pub profile_name: Option<String>,
+    /// A Netscape `cookies.txt` or browser-extension JSON export to log in with.
+    pub cookies_file: Option<String>,
+    /// Raw `Cookie` header for the Netflix account; `NETFLIX_COOKIES` takes precedence.
+    pub cookies: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ShahidConfig {
+    /// Two-letter country code; Shahid's catalogue and geo-blocking depend on it.
+    pub country: String,
+    /// `en` or `ar`, for titles and descriptions.
+    pub language: String,
+}
+
+impl Default for ShahidConfig {
+    fn default() -> Self {
+        ShahidConfig { country: "EG".to_string(), language: "en".to_string() }
+    }
+}
+
+/// Where browser failure bundles go and how many are kept.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DiagnosticsConfig {
+    pub dir: String,
+    /// Oldest bundles are deleted once all of them together are bigger than this.
+    pub max_total_mb: u64,
+    pub max_age_days: u64,
+    /// Posts the report and screenshot of each bundle to `admin.channel_id`.
+    pub attach_to_admin: bool,
+}
+
+impl Default for DiagnosticsConfig {
+    fn default() -> Self {
+        DiagnosticsConfig { dir: "diagnostics".to_string(), max_total_mb: 200, max_age_days: 7, attach_to_admin: true }
+    }
+}
+
+/// How the streaming browser is launched; checked by the preflight at startup.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BrowserConfig {
+    /// Where chromedriver (or msedgedriver) listens.
+    pub driver_url: String,
+    /// Chrome or Edge executable; the driver picks the installed one when unset.
+    pub binary: Option<String>,
+    pub headless: bool,
+    /// `[width, height]`; the window is maximized when unset.
+    pub window_size: Option<[u32; 2]>,
+    /// Extra switches. Setting this replaces the defaults, including the user agent; the
+    /// switches the stream needs (tab capture and auto-accepted capture) are always added.
+    pub args: Vec<String>,
+    /// Unpacked extensions, or folders of them, to load. When empty, an `Extensions`
+    /// folder next to the executable is loaded if there is one.
+    pub extensions: Vec<String>,
+    /// `--user-data-dir`, to keep logins between sessions; a fresh profile is used when unset.
+    pub profile_dir: Option<String>,
+}
+
+impl Default for BrowserConfig {
+    fn default() -> Self {
+        BrowserConfig {
+            driver_url: "http://localhost:50000".to_string(),
+            binary: None,
+            headless: false,
+            window_size: None,
+            args: [
+                "--ignore-ssl-errors=yes",
+                "--ignore-certificate-errors",
+                "--disable-gpu",
+                "--enable-chrome-browser-cloud-management",
+                "--enable-javascript",
+                "--disable-blink-features=AutomationControlled",
+                "--user-agent=Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.0.0",
+            ]
+            .map(String::from)
+            .to_vec(),
+            extensions: Vec::new(),
+            profile_dir: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let path = std::env::var("MOVIEBOT_CONFIG").unwrap_or_else(|_| "moviebot.toml".to_string());
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents).unwrap_or_else(|e| {
+                eprintln!("Failed to parse {}: {}, using defaults", path, e);
+                Config::default()
+            }),
+            Err(_) => Config::default(),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_config_keeps_defaults() {
+        let config = Config::parse(
+            r#"
+            [netflix.profile]
+            browser_name = "Chrome"
+            browser_version = "130"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.netflix.profile.browser_name, "Chrome");
+        assert_eq!(config.netflix.profile.esn_prefix, NetflixProfile::default().esn_prefix);
+        assert!(config.netflix.cookies.is_none());
+        assert_eq!(config.shahid.country, "EG");
+        assert!(config.scrapers.is_empty());
+        assert!(config.selectors.is_empty());
+        assert_eq!(config.browser.driver_url, "http://localhost:50000");
+    }
+
+    #[test]
+    fn browser_section_overrides_launch_settings() {
+        let config = Config::parse(
+            r#"
+            [browser]
+            driver_url = "http://127.0.0.1:9515"
+            headless = true
+            window_size = [1280, 720]
+            extensions = ["/opt/moviebot/extensions"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.browser.driver_url, "http://127.0.0.1:9515");
+        assert!(config.browser.headless);
+        assert_eq!(config.browser.window_size, Some([1280, 720]));
+        assert_eq!(config.browser.extensions, vec!["/opt/moviebot/extensions"]);
+        assert!(config.browser.args.iter().any(|arg| arg.starts_with("--user-agent=")));
+    }
+
+    #[test]
+    fn selector_overrides_are_keyed_by_element() {
+        let config = Config::parse(
+            r#"
+            [selectors]
+            "netflix.skip_forward" = ["button.forward", "xpath://button[2]"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.selectors["netflix.skip_forward"], vec!["button.forward", "xpath://button[2]"]);
+    }
+}
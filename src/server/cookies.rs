@@ -0,0 +1,278 @@
+//! One cookie jar for both the HTTP clients and the WebDriver session, loadable from
+//! a Netscape `cookies.txt` or a browser-extension JSON export.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+use thirtyfour::{Cookie, SameSite};
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: Option<String>,
+    /// Unix seconds; `None` for session cookies.
+    pub expires: Option<i64>,
+}
+
+impl StoredCookie {
+    fn new(name: &str, value: &str, domain: &str) -> Self {
+        StoredCookie {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain: domain.to_string(),
+            path: "/".to_string(),
+            secure: true,
+            http_only: false,
+            same_site: None,
+            expires: None,
+        }
+    }
+
+    /// Whether a browser would send this cookie to `domain` (given without a leading dot).
+    fn matches_domain(&self, domain: &str) -> bool {
+        let own = self.domain.trim_start_matches('.').to_ascii_lowercase();
+        own == domain || own.ends_with(&format!(".{}", domain))
+    }
+
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires.is_some_and(|expires| expires > 0 && expires <= now)
+    }
+
+    /// The cookie as WebDriver's add-cookie command takes it, for a browser on `site`
+    /// (given without a leading dot). WebDriver rejects cookies for any other host than
+    /// the current page's, so ones for `site` or its subdomains are widened to `.site`.
+    /// WebDriver can't set httpOnly, so that flag only matters to the HTTP side.
+    pub fn to_webdriver(&self, site: &str) -> Cookie {
+        let mut cookie = Cookie::new(&self.name, &self.value);
+        if self.matches_domain(site) {
+            cookie.set_domain(format!(".{}", site));
+        } else {
+            cookie.set_domain(&self.domain);
+        }
+        cookie.set_path(&self.path);
+        cookie.set_secure(self.secure);
+        if let Some(expires) = self.expires {
+            cookie.set_expiry(expires);
+        }
+        match self.same_site.as_deref().map(str::to_lowercase).as_deref() {
+            Some("strict") => cookie.set_same_site(SameSite::Strict),
+            Some("lax") => cookie.set_same_site(SameSite::Lax),
+            Some("none") | Some("no_restriction") => cookie.set_same_site(SameSite::None),
+            _ => {}
+        }
+        cookie
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CookieJar {
+    cookies: Vec<StoredCookie>,
+}
+
+impl CookieJar {
+    /// Parses a `Cookie` header value (`name=value; name2=value2`); the header
+    /// doesn't say where the cookies belong, so they're all given `domain`.
+    pub fn parse_header(header: &str, domain: &str) -> Self {
+        let cookies = header
+            .split(';')
+            .filter_map(|pair| pair.trim().split_once('='))
+            .map(|(name, value)| StoredCookie::new(name.trim(), value.trim(), domain))
+            .collect();
+        CookieJar { cookies }
+    }
+
+    /// Reads a cookie export, picking the format from the contents, and drops expired entries.
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path).map_err(|e| format!("Couldn't read cookies from {}: {}", path, e))?;
+        let cookies = match contents.trim_start().chars().next() {
+            Some('[') | Some('{') => parse_json(&contents)?,
+            _ => parse_netscape(&contents),
+        };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs() as i64).unwrap_or(0);
+        Ok(CookieJar { cookies: cookies.into_iter().filter(|cookie| !cookie.is_expired(now)).collect() })
+    }
+
+    /// Keeps the cookies for `domain` and its subdomains, so a whole-browser export
+    /// doesn't leak other sites' cookies into the requests or the WebDriver session.
+    pub fn for_domain(self, domain: &str) -> Self {
+        CookieJar { cookies: self.cookies.into_iter().filter(|cookie| cookie.matches_domain(domain)).collect() }
+    }
+
+    pub fn cookies(&self) -> &[StoredCookie] {
+        &self.cookies
+    }
+
+    pub fn header(&self) -> String {
+        self.cookies
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// `domain  include-subdomains  path  secure  expiry  name  value`, tab separated.
+/// curl marks httpOnly cookies by prefixing the domain with `#HttpOnly_`.
+fn parse_netscape(contents: &str) -> Vec<StoredCookie> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+                Some(rest) => (rest, true),
+                None if line.starts_with('#') => return None,
+                None => (line, false),
+            };
+            let fields: Vec<&str> = line.trim_end_matches('\r').split('\t').collect();
+            let [domain, _, path, secure, expires, name, value] = fields[..] else { return None };
+            let expires = expires.parse::<i64>().ok().filter(|expires| *expires > 0);
+            Some(StoredCookie {
+                path: path.to_string(),
+                secure: secure.eq_ignore_ascii_case("TRUE"),
+                http_only,
+                expires,
+                ..StoredCookie::new(name, value, domain)
+            })
+        })
+        .collect()
+}
+
+/// The layout used by EditThisCookie, Cookie-Editor and Chrome's own devtools export.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedCookie {
+    name: String,
+    value: String,
+    domain: String,
+    #[serde(default = "root_path")]
+    path: String,
+    #[serde(default)]
+    secure: bool,
+    #[serde(default)]
+    http_only: bool,
+    #[serde(default)]
+    same_site: Option<String>,
+    #[serde(default, alias = "expires")]
+    expiration_date: Option<f64>,
+    #[serde(default)]
+    session: bool,
+}
+
+fn root_path() -> String {
+    "/".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonExport {
+    List(Vec<ExportedCookie>),
+    Wrapped { cookies: Vec<ExportedCookie> },
+}
+
+fn parse_json(contents: &str) -> Result<Vec<StoredCookie>, Error> {
+    let exported = match serde_json::from_str(contents)? {
+        JsonExport::List(cookies) | JsonExport::Wrapped { cookies } => cookies,
+    };
+    Ok(exported
+        .into_iter()
+        .map(|cookie| StoredCookie {
+            name: cookie.name,
+            value: cookie.value,
+            domain: cookie.domain,
+            path: cookie.path,
+            secure: cookie.secure,
+            http_only: cookie.http_only,
+            same_site: cookie.same_site.filter(|same_site| same_site != "unspecified"),
+            expires: cookie.expiration_date.filter(|_| !cookie.session).map(|expires| expires as i64),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETSCAPE: &str = "# Netscape HTTP Cookie File\n\
+        .netflix.com\tTRUE\t/\tTRUE\t4102444800\tnfvdid\tabc\n\
+        #HttpOnly_.netflix.com\tTRUE\t/\tTRUE\t4102444800\tNetflixId\tv%3D3\n\
+        .netflix.com\tTRUE\t/\tFALSE\t1000\tstale\tgone\n\
+        not a cookie line\n";
+
+    const JSON_EXPORT: &str = r#"[
+        { "domain": ".netflix.com", "name": "SecureNetflixId", "value": "v%3D2", "path": "/",
+          "secure": true, "httpOnly": true, "sameSite": "strict", "expirationDate": 4102444800.5 },
+        { "domain": ".netflix.com", "name": "profilesNewSession", "value": "0",
+          "sameSite": "unspecified", "session": true, "expirationDate": 1000 }
+    ]"#;
+
+    fn load(contents: &str) -> CookieJar {
+        let path = std::env::temp_dir().join(format!("moviebot-cookies-{}.txt", rand::random::<u32>()));
+        std::fs::write(&path, contents).unwrap();
+        let jar = CookieJar::load(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(path).unwrap();
+        jar
+    }
+
+    #[test]
+    fn reads_netscape_files() {
+        let jar = load(NETSCAPE);
+        assert_eq!(jar.header(), "nfvdid=abc; NetflixId=v%3D3");
+        assert!(jar.cookies()[1].http_only);
+        assert_eq!(jar.cookies()[0].expires, Some(4102444800));
+    }
+
+    #[test]
+    fn reads_extension_json_exports() {
+        let jar = load(JSON_EXPORT);
+        let secure_id = &jar.cookies()[0];
+        assert!(secure_id.http_only);
+        assert_eq!(secure_id.same_site.as_deref(), Some("strict"));
+        assert_eq!(secure_id.expires, Some(4102444800));
+        assert_eq!(jar.cookies()[1].expires, None, "session cookies never expire from the jar");
+
+        let webdriver = secure_id.to_webdriver("netflix.com");
+        assert_eq!(webdriver.expiry, Some(4102444800));
+        assert!(matches!(webdriver.same_site, Some(SameSite::Strict)));
+    }
+
+    #[test]
+    fn exports_are_narrowed_to_one_site() {
+        let jar = load(r#"[
+            { "domain": ".netflix.com", "name": "NetflixId", "value": "a" },
+            { "domain": "www.netflix.com", "name": "nfvdid", "value": "b" },
+            { "domain": ".google.com", "name": "SID", "value": "c" },
+            { "domain": "notnetflix.com", "name": "id", "value": "d" }
+        ]"#)
+        .for_domain("netflix.com");
+        assert_eq!(jar.header(), "NetflixId=a; nfvdid=b");
+        assert!(jar.cookies().iter().all(|cookie| cookie.domain.ends_with("netflix.com")));
+    }
+
+    #[test]
+    fn host_only_cookies_are_set_for_the_whole_site() {
+        let jar = load(r#"[
+            { "domain": "www.netflix.com", "hostOnly": true, "name": "nfvdid", "value": "b" },
+            { "domain": ".netflix.com", "name": "NetflixId", "value": "a" },
+            { "domain": ".google.com", "name": "SID", "value": "c" }
+        ]"#);
+        let domains: Vec<_> = jar.cookies().iter().map(|cookie| cookie.to_webdriver("netflix.com").domain).collect();
+        assert_eq!(domains, vec![
+            Some(".netflix.com".to_string()),
+            Some(".netflix.com".to_string()),
+            Some(".google.com".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn header_cookies_get_the_given_domain() {
+        let jar = CookieJar::parse_header("NetflixId=abc; SecureNetflixId=def;  nfvdid=ghi;", ".netflix.com");
+        assert_eq!(jar.header(), "NetflixId=abc; SecureNetflixId=def; nfvdid=ghi");
+        assert_eq!(jar.cookies()[2].domain, ".netflix.com");
+    }
+}
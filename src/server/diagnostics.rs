@@ -0,0 +1,253 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::server::{browser::BrowserBackend, config::{DiagnosticsConfig, CONFIG}};
+
+/// Bundles captured by `capture`, for whoever forwards them to the admins.
+pub static DIAGNOSTICS: Lazy<broadcast::Sender<Bundle>> = Lazy::new(|| broadcast::channel(8).0);
+
+/// A saved diagnostics bundle.
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    pub dir: PathBuf,
+    pub step: String,
+    pub error: String,
+}
+
+impl Bundle {
+    pub fn screenshot(&self) -> Option<PathBuf> {
+        Some(self.dir.join("screenshot.png")).filter(|path| path.exists())
+    }
+
+    pub fn report(&self) -> PathBuf {
+        self.dir.join("report.json")
+    }
+}
+
+/// The named steps of a browser flow and when each began, so a failure can say
+/// which step it happened in and how long everything took.
+pub struct Trace {
+    started: Instant,
+    steps: Mutex<Vec<(String, Duration)>>,
+}
+
+impl Default for Trace {
+    fn default() -> Self {
+        Trace { started: Instant::now(), steps: Mutex::new(Vec::new()) }
+    }
+}
+
+impl Trace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn step(&self, name: &str) {
+        self.steps.lock().unwrap().push((name.to_string(), self.started.elapsed()));
+    }
+
+    pub fn current(&self) -> String {
+        self.steps.lock().unwrap().last().map(|(name, _)| name.clone()).unwrap_or_else(|| "startup".to_string())
+    }
+
+    fn timings(&self) -> Vec<StepTiming> {
+        let steps = self.steps.lock().unwrap();
+        let total = self.started.elapsed();
+        steps
+            .iter()
+            .enumerate()
+            .map(|(index, (name, began))| {
+                let ended = steps.get(index + 1).map(|(_, next)| *next).unwrap_or(total);
+                StepTiming { step: name.clone(), started_ms: began.as_millis(), took_ms: (ended - *began).as_millis() }
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize)]
+struct StepTiming {
+    step: String,
+    started_ms: u128,
+    took_ms: u128,
+}
+
+#[derive(Serialize)]
+struct Report {
+    step: String,
+    error: String,
+    url: Option<String>,
+    captured_at: u64,
+    elapsed_ms: u128,
+    steps: Vec<StepTiming>,
+    /// Parts of the bundle that couldn't be captured, such as the screenshot of a crashed tab.
+    missing: Vec<String>,
+}
+
+/// Saves what the browser looked like when `error` happened: screenshot, url, page
+/// source, console logs, the failing step and step timings. Capturing is best effort;
+/// whatever fails is listed in the report instead. Old bundles are pruned afterwards.
+pub async fn capture(driver: Option<&dyn BrowserBackend>, trace: &Trace, error: &str) -> std::io::Result<Bundle> {
+    let bundle = save(&CONFIG.diagnostics, driver, trace, error).await?;
+    let _ = DIAGNOSTICS.send(bundle.clone());
+    Ok(bundle)
+}
+
+/// Captures a bundle for a failed browser flow and says where it went.
+pub async fn record_failure(driver: Option<&dyn BrowserBackend>, trace: &Trace, error: &str) {
+    match capture(driver, trace, error).await {
+        Ok(bundle) => eprintln!("Browser failed during '{}', diagnostics saved to {:?}", bundle.step, bundle.dir),
+        Err(err) => eprintln!("Browser failed during '{}' ({}) and the diagnostics couldn't be saved: {}", trace.current(), error, err),
+    }
+}
+
+async fn save(config: &DiagnosticsConfig, driver: Option<&dyn BrowserBackend>, trace: &Trace, error: &str) -> std::io::Result<Bundle> {
+    let captured_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let step = trace.current();
+    let dir = Path::new(&config.dir).join(format!("{}-{}", captured_at.as_millis(), slug(&step)));
+    fs::create_dir_all(&dir)?;
+
+    let mut missing = Vec::new();
+    let mut url = None;
+    match driver {
+        Some(driver) => {
+            match driver.current_url().await {
+                Ok(current) => url = Some(current),
+                Err(err) => missing.push(format!("url: {}", err)),
+            }
+            match driver.screenshot().await {
+                Ok(png) => fs::write(dir.join("screenshot.png"), png)?,
+                Err(err) => missing.push(format!("screenshot: {}", err)),
+            }
+            match driver.page_source().await {
+                Ok(source) => fs::write(dir.join("page.html"), source)?,
+                Err(err) => missing.push(format!("page source: {}", err)),
+            }
+            match driver.console_logs().await {
+                Ok(lines) => fs::write(dir.join("console.log"), lines.join("\n"))?,
+                Err(err) => missing.push(format!("console logs: {}", err)),
+            }
+        }
+        None => missing.push("browser: it never started".to_string()),
+    }
+
+    let report = Report {
+        step: step.clone(),
+        error: error.to_string(),
+        url,
+        captured_at: captured_at.as_secs(),
+        elapsed_ms: trace.started.elapsed().as_millis(),
+        steps: trace.timings(),
+        missing,
+    };
+    fs::write(dir.join("report.json"), serde_json::to_vec_pretty(&report)?)?;
+
+    if let Err(err) = prune(Path::new(&config.dir), config) {
+        eprintln!("Failed to prune diagnostics: {}", err);
+    }
+    Ok(Bundle { dir, step, error: error.to_string() })
+}
+
+/// Deletes bundles older than `max_age_days`, then the oldest ones until the rest fit in `max_total_mb`.
+fn prune(root: &Path, config: &DiagnosticsConfig) -> std::io::Result<()> {
+    let max_age = Duration::from_secs(config.max_age_days * 24 * 60 * 60);
+    let mut bundles = Vec::new();
+    for entry in fs::read_dir(root)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let age = entry.metadata()?.modified()?.elapsed().unwrap_or_default();
+        if age > max_age {
+            fs::remove_dir_all(entry.path())?;
+        } else {
+            bundles.push((entry.file_name(), entry.path(), dir_size(&entry.path())?));
+        }
+    }
+
+    // Names start with the capture time, so sorting by name puts the oldest first.
+    bundles.sort();
+    let mut total: u64 = bundles.iter().map(|(_, _, size)| size).sum();
+    let limit = config.max_total_mb * 1024 * 1024;
+    for (_, path, size) in bundles {
+        if total <= limit {
+            break;
+        }
+        fs::remove_dir_all(path)?;
+        total -= size;
+    }
+    Ok(())
+}
+
+fn dir_size(dir: &Path) -> std::io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(dir)? {
+        size += entry?.metadata()?.len();
+    }
+    Ok(size)
+}
+
+fn slug(step: &str) -> String {
+    step.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::browser::fake::{BrowserCall, FakeBrowser};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("moviebot-diagnostics-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn bundle_has_the_page_and_the_failing_step() {
+        let browser = FakeBrowser::new();
+        browser.goto("https://discord.com/channels/1").await.unwrap();
+        let trace = Trace::new();
+        trace.step("discord login");
+        trace.step("voice channel");
+
+        let root = temp_dir("bundle");
+        let config = DiagnosticsConfig { dir: root.to_string_lossy().to_string(), ..DiagnosticsConfig::default() };
+        let bundle = save(&config, Some(&browser), &trace, "gave up waiting for discord.channel").await.unwrap();
+        assert_eq!(bundle.step, "voice channel");
+        assert!(bundle.dir.to_string_lossy().ends_with("-voice-channel"));
+        assert!(browser.calls().contains(&BrowserCall::Screenshot));
+        assert!(bundle.dir.join("page.html").exists());
+
+        let report: serde_json::Value = serde_json::from_slice(&fs::read(bundle.report()).unwrap()).unwrap();
+        assert_eq!(report["url"], "https://discord.com/channels/1");
+        assert_eq!(report["steps"][0]["step"], "discord login");
+        assert_eq!(report["error"], "gave up waiting for discord.channel");
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn prune_drops_the_oldest_bundles_over_the_size_limit() {
+        let root = temp_dir("prune");
+        for name in ["100-a", "200-b", "300-c"] {
+            fs::create_dir_all(root.join(name)).unwrap();
+            fs::write(root.join(name).join("page.html"), vec![b'x'; 600 * 1024]).unwrap();
+        }
+        let config = DiagnosticsConfig { max_total_mb: 1, ..DiagnosticsConfig::default() };
+        prune(&root, &config).unwrap();
+
+        let mut left: Vec<String> = fs::read_dir(&root).unwrap().map(|entry| entry.unwrap().file_name().to_string_lossy().to_string()).collect();
+        left.sort();
+        assert_eq!(left, vec!["300-c"]);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
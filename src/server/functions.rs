@@ -1,7 +1,7 @@
-use thirtyfour::{error::WebDriverResult, By, WebDriver};
-use crate::server::classes::fasel::Fasel;
+use thirtyfour::error::WebDriverResult;
+use crate::server::{browser::{selectors::SELECTORS, wait::{wait_for, Condition}, BrowserBackend}, diagnostics::Trace, classes::{fasel::Fasel, shahid::Shahid}};
 use tokio::sync::Mutex;
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use once_cell::sync::Lazy;
 
 use super::classes::netflix::Netflix;
@@ -36,19 +36,39 @@ impl Default for DiscordData {
     }
 }
 
+/// Discord's web app is slow to load and to join voice, so its steps get the most time.
+const DISCORD_TIMEOUT: Duration = Duration::from_secs(30);
+const WINDOW_TIMEOUT: Duration = Duration::from_secs(10);
+
 static SHOULD_QUIT: Lazy<Mutex<bool>> = Lazy::new(|| Mutex::new(false));
-pub static DRIVER_INSTANCE: Lazy<Mutex<Option<WebDriver>>> = Lazy::new(|| Mutex::new(None));
+pub static DRIVER_INSTANCE: Lazy<Mutex<Option<Arc<dyn BrowserBackend>>>> = Lazy::new(|| Mutex::new(None));
+static PLAYING_SERVICE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
 
-pub async fn set_driver(driver: WebDriver) {
+pub async fn set_driver(driver: Arc<dyn BrowserBackend>) {
     let mut instance = DRIVER_INSTANCE.lock().await;
     *instance = Some(driver);
 }
 
+pub async fn current_driver() -> Option<Arc<dyn BrowserBackend>> {
+    DRIVER_INSTANCE.lock().await.clone()
+}
+
+/// The streaming service the browser was last started for, which decides how the controls work.
+pub async fn current_service() -> Option<String> {
+    PLAYING_SERVICE.lock().await.clone()
+}
+
+/// Everything but Netflix and Shahid is played through an embedded JW Player.
+pub fn uses_jwplayer(service: &str) -> bool {
+    service != "Netflix" && service != "Shahid"
+}
+
 pub async fn quit_browser() -> WebDriverResult<()> {
     let mut instance = DRIVER_INSTANCE.lock().await;
     if let Some(driver) = instance.take() {
         driver.quit().await?;
     }
+    *PLAYING_SERVICE.lock().await = None;
     let mut should_quit = SHOULD_QUIT.lock().await;
     *should_quit = true; 
     Ok(())
@@ -64,31 +84,46 @@ pub async fn keep_browser_alive() {
     }
 }
 
-pub async fn start_discord(driver: WebDriver, discord_data: Option<DiscordData>, url: &str, r#type: &str) -> WebDriverResult<()> {
+pub async fn start_discord(driver: Arc<dyn BrowserBackend>, discord_data: Option<DiscordData>, url: &str, r#type: &str, trace: &Trace) -> WebDriverResult<()> {
     set_driver(driver.clone()).await;
+    *PLAYING_SERVICE.lock().await = Some(r#type.to_string());
 
+    trace.step("discord login");
     driver.goto("https://discord.com/login").await?;
     //Put token here
-    driver.execute(return_script("Token"), Vec::new()).await?;
+    driver.execute(&return_script("Token"), Vec::new()).await?;
     driver.goto("https://discord.com/channels/1000710976343134289/1010686745840472104").await?;
-    tokio::time::sleep(Duration::from_secs(4)).await;
-    driver.find(By::Css(format!("[data-list-item-id='channels___{}']", discord_data.unwrap_or_default().id))).await?.click().await?;
-    tokio::time::sleep(Duration::from_secs(2)).await;
-
+    // Discord bounces back to /login when the token didn't take.
+    wait_for(driver.as_ref(), "Discord login", Condition::UrlContains("/channels/".to_string()), DISCORD_TIMEOUT).await?;
+    trace.step("voice channel");
+    let channel_id = discord_data.unwrap_or_default().id;
+    let channel = SELECTORS.wait(driver.as_ref(), "discord.channel", &[("id", &channel_id)], Condition::Clickable, DISCORD_TIMEOUT).await?;
+    driver.click(&channel).await?;
+    // The Go Live button shows up once the voice channel is joined.
+    let go_live = SELECTORS.wait(driver.as_ref(), "discord.go_live", &[], Condition::Clickable, DISCORD_TIMEOUT).await?;
+
+    trace.step("go live");
     driver.execute("window.open('about:blank')", Vec::new()).await?;
+    wait_for(driver.as_ref(), "blank window", Condition::Windows(2), WINDOW_TIMEOUT).await?;
     let windows = driver.windows().await?;
-    driver.switch_to_window(windows[1].clone()).await?;
+    driver.switch_to_window(&windows[1]).await?;
 
-    driver.find(By::XPath("//*[@id=\"app-mount\"]/div[2]/div[1]/div[1]/div/div[2]/div/div/div/div/div[1]/section/div[1]/div/div[2]/button[2]")).await?.click().await?;
-    tokio::time::sleep(Duration::from_secs(3)).await;
+    driver.click(&go_live).await?;
+    wait_for(driver.as_ref(), "Go Live window", Condition::Windows(3), WINDOW_TIMEOUT).await?;
 
-    driver.switch_to_window(windows[2].clone()).await?;
-    if r#type == "Fasel" {
-        let fasel = Fasel::new(driver.clone()).await;
-        fasel.start(url).await?;
-    } else if r#type == "Netflix" {
-        let netflix = Netflix::new(driver.clone()).await;
+    let windows = driver.windows().await?;
+    driver.switch_to_window(&windows[2]).await?;
+    trace.step(&format!("{} playback", r#type));
+    if r#type == "Netflix" {
+        let netflix = Netflix::new(driver.clone());
         netflix.start(url).await?;
+    } else if r#type == "Shahid" {
+        let shahid = Shahid::new(driver.clone());
+        shahid.start(url).await?;
+    } else {
+        // Fasel and the other scraped providers all embed JW Player.
+        let fasel = Fasel::new(driver.clone());
+        fasel.start(url).await?;
     }
 
     tokio::spawn(async {
@@ -97,3 +132,28 @@ pub async fn start_discord(driver: WebDriver, discord_data: Option<DiscordData>,
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::browser::{fake::{BrowserCall, FakeBrowser}, Locator};
+    use serde_json::json;
+
+    #[tokio::test(start_paused = true)]
+    async fn start_discord_shares_the_stream_window_before_playing() {
+        let browser = Arc::new(FakeBrowser::new());
+        browser.set_windows(&["discord", "blank", "player"]);
+        browser.script_result("window.frames.length", json!({ "player": true, "frames": 0 }));
+        browser.script_result("readyState >= 3", json!(true));
+        browser.script_result("getCaptionsList", json!({
+            "state": "playing", "position": 0, "duration": 5400, "volume": 100, "quality": 0, "captions": 0,
+        }));
+        start_discord(browser.clone(), None, "https://fasel.example/movie", "Fasel", &Trace::new()).await.unwrap();
+
+        let calls = browser.calls();
+        assert_eq!(calls[0], BrowserCall::Goto("https://discord.com/login".to_string()));
+        assert!(calls.contains(&BrowserCall::Click(Locator::css("[data-list-item-id='channels___1000710976343134293']"))));
+        let player = calls.iter().position(|call| *call == BrowserCall::SwitchToWindow("player".to_string())).unwrap();
+        assert_eq!(calls[player + 1], BrowserCall::Goto("https://fasel.example/movie".to_string()));
+    }
+}
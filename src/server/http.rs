@@ -0,0 +1,487 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+const REDACTED: &str = "REDACTED";
+
+/// Every provider request goes through this one pooled client so that it can be
+/// recorded to (or replayed from) the fixtures directory.
+pub static HTTP: Lazy<ProviderHttp> = Lazy::new(ProviderHttp::from_env);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMode {
+    Live = 0,
+    Record = 1,
+    Replay = 2,
+}
+
+impl HttpMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "live" => Some(HttpMode::Live),
+            "record" => Some(HttpMode::Record),
+            "replay" => Some(HttpMode::Replay),
+            _ => None,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => HttpMode::Record,
+            2 => HttpMode::Replay,
+            _ => HttpMode::Live,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(&self.body)
+    }
+}
+
+pub struct ProviderHttp {
+    client: Client,
+    mode: AtomicU8,
+    store: FixtureStore,
+}
+
+impl ProviderHttp {
+    /// Reads `MOVIEBOT_HTTP_MODE` (live, record or replay) and `MOVIEBOT_FIXTURES_DIR`.
+    pub fn from_env() -> Self {
+        let mode = std::env::var("MOVIEBOT_HTTP_MODE")
+            .ok()
+            .and_then(|value| HttpMode::parse(&value))
+            .unwrap_or(HttpMode::Live);
+        let dir = std::env::var("MOVIEBOT_FIXTURES_DIR").unwrap_or_else(|_| "fixtures".to_string());
+        Self::new(mode, FixtureStore::new(dir))
+    }
+
+    pub fn new(mode: HttpMode, store: FixtureStore) -> Self {
+        ProviderHttp {
+            client: Client::new(),
+            mode: AtomicU8::new(mode as u8),
+            store,
+        }
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn mode(&self) -> HttpMode {
+        HttpMode::from_u8(self.mode.load(Ordering::Relaxed))
+    }
+
+    pub fn set_mode(&self, mode: HttpMode) {
+        self.mode.store(mode as u8, Ordering::Relaxed);
+    }
+
+    pub fn store(&self) -> &FixtureStore {
+        &self.store
+    }
+
+    /// Sends `request`, recording or replaying it under `provider` depending on the mode.
+    pub async fn send(&self, provider: &str, request: RequestBuilder) -> Result<HttpResponse, Error> {
+        let request = request.build()?;
+        let recorded = RecordedRequest::from_request(&request);
+
+        if self.mode() == HttpMode::Replay {
+            return match self.store.load(provider, &recorded.key()) {
+                Some(fixture) => Ok(fixture.response.into_response()),
+                None => Err(format!("No fixture recorded for {} {}", recorded.method, recorded.url).into()),
+            };
+        }
+
+        let response = self.client.execute(request).await?;
+        let response = HttpResponse {
+            status: response.status(),
+            headers: response
+                .headers()
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_str().unwrap_or("").to_string()))
+                .collect(),
+            body: response.bytes().await?.to_vec(),
+        };
+
+        if self.mode() == HttpMode::Record {
+            let fixture = Fixture {
+                request: recorded,
+                response: RecordedResponse::from_response(&response),
+            };
+            if let Err(err) = self.store.save(provider, &fixture) {
+                eprintln!("Failed to record fixture: {:?}", err);
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fixture {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    fn from_request(request: &reqwest::Request) -> Self {
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+            .unwrap_or_default();
+        RecordedRequest {
+            method: request.method().to_string(),
+            url: redact_text(request.url().as_str()),
+            headers: request
+                .headers()
+                .iter()
+                .map(|(name, value)| redact_header(name.as_str(), value.to_str().unwrap_or("")))
+                .collect(),
+            body: redact_text(&body),
+        }
+    }
+
+    /// Identifies a request by method, path, query and body. The host is left out
+    /// so the same key is produced when the request reaches the stand-in server.
+    pub fn key(&self) -> String {
+        let path = match reqwest::Url::parse(&self.url) {
+            Ok(url) => match url.query() {
+                Some(query) => format!("{}?{}", url.path(), query),
+                None => url.path().to_string(),
+            },
+            Err(_) => self.url.clone(),
+        };
+        fixture_key(&self.method, &path, &self.body)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: RecordedBody,
+}
+
+/// A response body as it's written to a fixture: redacted text when it's UTF-8,
+/// otherwise the bytes as they came, so images and other binaries replay intact.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RecordedBody {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl RecordedBody {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => RecordedBody::Text(redact_text(text)),
+            Err(_) => RecordedBody::Bytes(bytes.to_vec()),
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            RecordedBody::Text(text) => text.into_bytes(),
+            RecordedBody::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn from_response(response: &HttpResponse) -> Self {
+        RecordedResponse {
+            status: response.status.as_u16(),
+            headers: response
+                .headers
+                .iter()
+                .map(|(name, value)| redact_header(name, value))
+                .collect(),
+            body: RecordedBody::from_bytes(&response.body),
+        }
+    }
+
+    fn into_response(self) -> HttpResponse {
+        HttpResponse {
+            status: StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK),
+            headers: self.headers,
+            body: self.body.into_bytes(),
+        }
+    }
+}
+
+pub struct FixtureStore {
+    dir: PathBuf,
+}
+
+impl FixtureStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FixtureStore { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn save(&self, provider: &str, fixture: &Fixture) -> Result<PathBuf, Error> {
+        let provider_dir = self.dir.join(provider);
+        std::fs::create_dir_all(&provider_dir)?;
+        let path = provider_dir.join(format!("{}.json", fixture.request.key()));
+        std::fs::write(&path, serde_json::to_string_pretty(fixture)?)?;
+        Ok(path)
+    }
+
+    pub fn load(&self, provider: &str, key: &str) -> Option<Fixture> {
+        let contents = std::fs::read_to_string(self.dir.join(provider).join(format!("{}.json", key))).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    /// Looks a key up across every provider directory, for the stand-in server.
+    pub fn find(&self, key: &str) -> Option<Fixture> {
+        std::fs::read_dir(&self.dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .find_map(|entry| self.load(&entry.file_name().to_string_lossy(), key))
+    }
+}
+
+/// Serves recorded fixtures over plain HTTP so that anything able to point at a
+/// base URL can run against them instead of the real providers.
+pub async fn serve_fixtures(store: FixtureStore, addr: &str) -> Result<(), Error> {
+    let listener = TcpListener::bind(addr).await?;
+    println!("Serving fixtures from {:?} on http://{}", store.dir(), listener.local_addr()?);
+    let store = std::sync::Arc::new(store);
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let store = store.clone();
+        tokio::spawn(async move {
+            if let Err(err) = handle_fixture_request(stream, &store).await {
+                eprintln!("Fixture server error: {:?}", err);
+            }
+        });
+    }
+}
+
+async fn handle_fixture_request(mut stream: TcpStream, store: &FixtureStore) -> Result<(), Error> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break position + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or("").split_whitespace();
+    let method = request_line.next().unwrap_or("GET").to_string();
+    let path = request_line.next().unwrap_or("/").to_string();
+    let content_length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    while buffer.len() < header_end + content_length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    let body = String::from_utf8_lossy(&buffer[header_end..]).into_owned();
+    let key = fixture_key(&method, &redact_text(&path), &redact_text(&body));
+
+    let (status, content_type, body) = match store.find(&key) {
+        Some(fixture) => {
+            let content_type = fixture
+                .response
+                .headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+                .map(|(_, value)| value.clone())
+                .unwrap_or_else(|| "application/json".to_string());
+            (fixture.response.status, content_type, fixture.response.body.into_bytes())
+        }
+        None => {
+            println!("No fixture for {} {}", method, path);
+            (404, "text/plain".to_string(), format!("No fixture recorded for {} {}", method, path).into_bytes())
+        }
+    };
+
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        StatusCode::from_u16(status).ok().and_then(|s| s.canonical_reason()).unwrap_or(""),
+        content_type,
+        body.len(),
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// FNV-1a over the request identity, so file names stay stable across runs and toolchains.
+fn fixture_key(method: &str, path: &str, body: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in method.bytes().chain([0]).chain(path.bytes()).chain([0]).chain(body.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{}-{:016x}", method.to_lowercase(), hash)
+}
+
+const SECRET_HEADERS: [&str; 5] = [
+    "cookie",
+    "set-cookie",
+    "authorization",
+    "x-netflix.request.client.user.guid",
+    "x-netflix.esn",
+];
+
+fn redact_header(name: &str, value: &str) -> (String, String) {
+    let name = name.to_lowercase();
+    if SECRET_HEADERS.contains(&name.as_str()) {
+        (name, REDACTED.to_string())
+    } else {
+        (name, redact_text(value))
+    }
+}
+
+static SECRET_PATTERNS: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    vec![
+        // JSON fields carrying tokens, account GUIDs and device ids.
+        (
+            Regex::new(r#""(authURL|authUrl|userGuid|guid|ownerGuid|profileGuid|currentProfileGuid|accountOwnerGuid|esn|token|accessToken|refreshToken)"\s*:\s*"[^"]*""#).unwrap(),
+            r#""$1":"REDACTED""#,
+        ),
+        // Form-encoded and query-string tokens.
+        (Regex::new(r"(authURL|token|access_token)=[^&\s]*").unwrap(), "$1=REDACTED"),
+        // Netflix session cookies leaking into bodies.
+        (Regex::new(r"(NetflixId|SecureNetflixId|nfvdid)=[^;&\s]*").unwrap(), "$1=REDACTED"),
+    ]
+});
+
+pub fn redact_text(text: &str) -> String {
+    SECRET_PATTERNS
+        .iter()
+        .fold(text.to_string(), |text, (pattern, replacement)| {
+            pattern.replace_all(&text, *replacement).into_owned()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_tokens_and_guids() {
+        let body = r#"path=%5B%22search%22%5D&authURL=1739263844285.2XpQna0eN71gFJg%2Bgz"#;
+        assert_eq!(redact_text(body), "path=%5B%22search%22%5D&authURL=REDACTED");
+
+        let json = r#"{"userGuid": "JYH2XQN2E5BRZHXFX2IFOXEV5Y", "title": "Dark"}"#;
+        assert_eq!(redact_text(json), r#"{"userGuid":"REDACTED", "title": "Dark"}"#);
+
+        let guid = r#"{"currentProfileGuid":"JYH2XQN2E5BRZHXFX2IFOXEV5Y"}"#;
+        assert_eq!(redact_text(guid), r#"{"currentProfileGuid":"REDACTED"}"#);
+        assert_eq!(redact_header("Cookie", "NetflixId=abc").1, REDACTED);
+    }
+
+    #[test]
+    fn leaves_look_alike_values_outside_guid_fields() {
+        let json = r#"{"title": "ABCDEFGHIJKLMNOPQRSTUVWXYZ", "trackId": "2XPQNA0EN71GFJG0GZ00000000"}"#;
+        assert_eq!(redact_text(json), json);
+    }
+
+    #[test]
+    fn binary_bodies_are_recorded_byte_for_byte() {
+        let png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0xff, 0x00];
+        let response = HttpResponse { status: StatusCode::OK, headers: Vec::new(), body: png.clone() };
+        let recorded = RecordedResponse::from_response(&response);
+        assert_eq!(recorded.body, RecordedBody::Bytes(png.clone()));
+
+        let json = serde_json::to_string(&recorded).unwrap();
+        let replayed: RecordedResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(replayed.into_response().body, png);
+    }
+
+    #[test]
+    fn key_ignores_host_and_secrets() {
+        let live = RecordedRequest {
+            method: "POST".to_string(),
+            url: "https://www.netflix.com/graphql?x=1".to_string(),
+            headers: Vec::new(),
+            body: redact_text("authURL=first"),
+        };
+        let replayed = RecordedRequest {
+            url: "http://127.0.0.1:8080/graphql?x=1".to_string(),
+            body: redact_text("authURL=second"),
+            ..live.clone()
+        };
+        assert_eq!(live.key(), replayed.key());
+    }
+
+    #[test]
+    fn store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("moviebot-fixtures-{}", std::process::id()));
+        let store = FixtureStore::new(&dir);
+        let fixture = Fixture {
+            request: RecordedRequest {
+                method: "GET".to_string(),
+                url: "https://api3.shahid.net/proxy/v2.1/t-search?request=x".to_string(),
+                headers: Vec::new(),
+                body: String::new(),
+            },
+            response: RecordedResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: RecordedBody::Text("{}".to_string()),
+            },
+        };
+        store.save("shahid", &fixture).unwrap();
+        let key = fixture.request.key();
+        assert!(store.load("shahid", &key).is_some());
+        assert!(store.find(&key).is_some());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,2 +1,7 @@
+pub mod browser;
 pub mod classes;
+pub mod config;
+pub mod cookies;
+pub mod diagnostics;
 pub mod functions;
+pub mod http;